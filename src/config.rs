@@ -5,9 +5,14 @@ use serde::{Deserialize, Serialize};
 
 use crate::error::CargoResult;
 use crate::ops::cargo;
+use crate::steps::hook::HookPoint;
 
+static DEFAULT_PRE_RELEASE_CHANNELS: std::sync::LazyLock<Vec<String>> =
+    std::sync::LazyLock::new(crate::ops::version::default_channels);
+
+// `deny_unknown_fields` doesn't work with `flatten`; instead, [`HookKey`] rejects unknown fields
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
-#[serde(deny_unknown_fields, default)]
+#[serde(default)]
 #[serde(rename_all = "kebab-case")]
 pub struct Config {
     #[serde(skip)]
@@ -21,21 +26,35 @@ pub struct Config {
     pub release: Option<bool>,
     pub publish: Option<bool>,
     pub verify: Option<bool>,
+    pub semver_check: Option<bool>,
+    pub publish_timeout: Option<u64>,
+    pub publish_poll_interval: Option<u64>,
     pub owners: Option<Vec<String>>,
     pub push: Option<bool>,
     pub push_options: Option<Vec<String>>,
     pub shared_version: Option<SharedVersion>,
+    pub groups: Option<std::collections::BTreeMap<String, Group>>,
     pub consolidate_commits: Option<bool>,
     pub pre_release_commit_message: Option<String>,
     pub pre_release_replacements: Option<Vec<Replace>>,
-    pub pre_release_hook: Option<Command>,
+    #[serde(flatten)]
+    pub hooks: std::collections::BTreeMap<HookKey, Command>,
+    pub changelog: Option<PathBuf>,
+    pub commit_rules: Option<Vec<CommitRule>>,
+    pub post_release_version: Option<String>,
+    pub post_release_replacements: Option<Vec<Replace>>,
+    pub post_release_commit_message: Option<String>,
     pub tag_message: Option<String>,
     pub tag_prefix: Option<String>,
     pub tag_name: Option<String>,
     pub tag: Option<bool>,
+    pub workspace_tag: Option<bool>,
     pub enable_features: Option<Vec<String>>,
     pub enable_all_features: Option<bool>,
     pub dependent_version: Option<DependentVersion>,
+    pub dependent_bump: Option<DependentBump>,
+    pub calver_format: Option<String>,
+    pub pre_release_channels: Option<Vec<String>>,
     pub metadata: Option<MetadataPolicy>,
     pub target: Option<String>,
     pub rate_limit: RateLimit,
@@ -65,6 +84,9 @@ impl Config {
             release: Some(empty.release()),
             publish: Some(empty.publish()),
             verify: Some(empty.verify()),
+            semver_check: Some(empty.semver_check()),
+            publish_timeout: Some(empty.publish_timeout()),
+            publish_poll_interval: Some(empty.publish_poll_interval()),
             owners: Some(empty.owners().to_vec()),
             push: Some(empty.push()),
             push_options: Some(
@@ -76,17 +98,27 @@ impl Config {
             shared_version: empty
                 .shared_version()
                 .map(|s| SharedVersion::Name(s.to_owned())),
+            groups: Some(empty.groups().clone()),
             consolidate_commits: Some(empty.consolidate_commits()),
             pre_release_commit_message: Some(empty.pre_release_commit_message().to_owned()),
             pre_release_replacements: Some(empty.pre_release_replacements().to_vec()),
-            pre_release_hook: empty.pre_release_hook().cloned(),
+            hooks: empty.hooks.clone(),
+            changelog: empty.changelog().map(|p| p.to_owned()),
+            commit_rules: Some(empty.commit_rules().to_vec()),
+            post_release_version: empty.post_release_version().map(|s| s.to_owned()),
+            post_release_replacements: Some(empty.post_release_replacements().to_vec()),
+            post_release_commit_message: Some(empty.post_release_commit_message().to_owned()),
             tag_message: Some(empty.tag_message().to_owned()),
             tag_prefix: None, // Skipping, its location dependent
             tag_name: Some(empty.tag_name().to_owned()),
             tag: Some(empty.tag()),
+            workspace_tag: Some(empty.workspace_tag()),
             enable_features: Some(empty.enable_features().to_vec()),
             enable_all_features: Some(empty.enable_all_features()),
             dependent_version: Some(empty.dependent_version()),
+            dependent_bump: Some(empty.dependent_bump()),
+            calver_format: Some(empty.calver_format().to_owned()),
+            pre_release_channels: Some(empty.pre_release_channels().to_vec()),
             metadata: Some(empty.metadata()),
             target: None,
             rate_limit: RateLimit::from_defaults(),
@@ -120,6 +152,15 @@ impl Config {
         if let Some(verify) = source.verify {
             self.verify = Some(verify);
         }
+        if let Some(semver_check) = source.semver_check {
+            self.semver_check = Some(semver_check);
+        }
+        if let Some(publish_timeout) = source.publish_timeout {
+            self.publish_timeout = Some(publish_timeout);
+        }
+        if let Some(publish_poll_interval) = source.publish_poll_interval {
+            self.publish_poll_interval = Some(publish_poll_interval);
+        }
         if let Some(owners) = source.owners.as_deref() {
             self.owners = Some(owners.to_owned());
         }
@@ -132,6 +173,9 @@ impl Config {
         if let Some(shared_version) = source.shared_version.clone() {
             self.shared_version = Some(shared_version);
         }
+        if let Some(groups) = source.groups.as_ref() {
+            self.groups = Some(groups.to_owned());
+        }
         if let Some(consolidate_commits) = source.consolidate_commits {
             self.consolidate_commits = Some(consolidate_commits);
         }
@@ -141,8 +185,23 @@ impl Config {
         if let Some(pre_release_replacements) = source.pre_release_replacements.as_deref() {
             self.pre_release_replacements = Some(pre_release_replacements.to_owned());
         }
-        if let Some(pre_release_hook) = source.pre_release_hook.as_ref() {
-            self.pre_release_hook = Some(pre_release_hook.to_owned());
+        for (key, command) in &source.hooks {
+            self.hooks.insert(*key, command.to_owned());
+        }
+        if let Some(changelog) = source.changelog.as_deref() {
+            self.changelog = Some(changelog.to_owned());
+        }
+        if let Some(commit_rules) = source.commit_rules.as_deref() {
+            self.commit_rules = Some(commit_rules.to_owned());
+        }
+        if let Some(post_release_version) = source.post_release_version.as_deref() {
+            self.post_release_version = Some(post_release_version.to_owned());
+        }
+        if let Some(post_release_replacements) = source.post_release_replacements.as_deref() {
+            self.post_release_replacements = Some(post_release_replacements.to_owned());
+        }
+        if let Some(post_release_commit_message) = source.post_release_commit_message.as_deref() {
+            self.post_release_commit_message = Some(post_release_commit_message.to_owned());
         }
         if let Some(tag_message) = source.tag_message.as_deref() {
             self.tag_message = Some(tag_message.to_owned());
@@ -156,6 +215,9 @@ impl Config {
         if let Some(tag) = source.tag {
             self.tag = Some(tag);
         }
+        if let Some(workspace_tag) = source.workspace_tag {
+            self.workspace_tag = Some(workspace_tag);
+        }
         if let Some(enable_features) = source.enable_features.as_deref() {
             self.enable_features = Some(enable_features.to_owned());
         }
@@ -165,6 +227,15 @@ impl Config {
         if let Some(dependent_version) = source.dependent_version {
             self.dependent_version = Some(dependent_version);
         }
+        if let Some(dependent_bump) = source.dependent_bump {
+            self.dependent_bump = Some(dependent_bump);
+        }
+        if let Some(calver_format) = source.calver_format.as_deref() {
+            self.calver_format = Some(calver_format.to_owned());
+        }
+        if let Some(pre_release_channels) = source.pre_release_channels.as_deref() {
+            self.pre_release_channels = Some(pre_release_channels.to_owned());
+        }
         if let Some(metadata) = source.metadata {
             self.metadata = Some(metadata);
         }
@@ -216,6 +287,21 @@ impl Config {
         self.verify.unwrap_or(true)
     }
 
+    /// Compare the public API against the last release before releasing
+    pub fn semver_check(&self) -> bool {
+        self.semver_check.unwrap_or(false)
+    }
+
+    /// Seconds to wait for a published crate to show up in the index
+    pub fn publish_timeout(&self) -> u64 {
+        self.publish_timeout.unwrap_or(300)
+    }
+
+    /// Seconds between the first checks of the index after publishing
+    pub fn publish_poll_interval(&self) -> u64 {
+        self.publish_poll_interval.unwrap_or(1)
+    }
+
     pub fn owners(&self) -> &[String] {
         self.owners.as_ref().map(|v| v.as_ref()).unwrap_or(&[])
     }
@@ -235,6 +321,37 @@ impl Config {
         self.shared_version.as_ref().and_then(|s| s.as_name())
     }
 
+    pub fn groups(&self) -> &std::collections::BTreeMap<String, Group> {
+        static EMPTY: std::collections::BTreeMap<String, Group> = std::collections::BTreeMap::new();
+        self.groups.as_ref().unwrap_or(&EMPTY)
+    }
+
+    /// The group each of `names` is a member of, leaving out those without a group
+    pub fn group_members<'n>(
+        &self,
+        names: impl IntoIterator<Item = &'n str>,
+    ) -> CargoResult<std::collections::HashMap<&'n str, &str>> {
+        let matchers = self
+            .groups()
+            .iter()
+            .map(|(group_name, group)| Ok((group_name.as_str(), group.matcher()?)))
+            .collect::<CargoResult<Vec<_>>>()?;
+        let mut members = std::collections::HashMap::new();
+        for name in names {
+            for (group_name, matcher) in &matchers {
+                if !matcher.is_match(name) {
+                    continue;
+                }
+                if let Some(existing) = members.insert(name, *group_name) {
+                    anyhow::bail!(
+                        "`{name}` is a member of both the `{existing}` and `{group_name}` groups"
+                    );
+                }
+            }
+        }
+        Ok(members)
+    }
+
     pub fn consolidate_commits(&self) -> bool {
         self.consolidate_commits.unwrap_or(self.is_workspace)
     }
@@ -258,21 +375,76 @@ impl Config {
             .unwrap_or(&[])
     }
 
-    pub fn pre_release_hook(&self) -> Option<&Command> {
-        self.pre_release_hook.as_ref()
+    /// The package's hook for `point`
+    pub fn hook(&self, point: HookPoint) -> Option<&Command> {
+        self.hooks.get(&HookKey {
+            point,
+            workspace: false,
+        })
     }
 
-    pub fn tag_message(&self) -> &str {
-        self.tag_message
+    /// The hook for `point` that runs once for the workspace
+    pub fn workspace_hook(&self, point: HookPoint) -> Option<&Command> {
+        self.hooks.get(&HookKey {
+            point,
+            workspace: true,
+        })
+    }
+
+    pub fn changelog(&self) -> Option<&Path> {
+        self.changelog.as_deref()
+    }
+
+    pub fn commit_rules(&self) -> &[CommitRule] {
+        self.commit_rules
+            .as_ref()
+            .map(|v| v.as_ref())
+            .unwrap_or(&[])
+    }
+
+    /// Version to move to after the release, like `{{major}}.{{minor}}.{{next_patch}}-dev`
+    pub fn post_release_version(&self) -> Option<&str> {
+        self.post_release_version.as_deref()
+    }
+
+    pub fn post_release_replacements(&self) -> &[Replace] {
+        self.post_release_replacements
+            .as_ref()
+            .map(|v| v.as_ref())
+            .unwrap_or(&[])
+    }
+
+    pub fn post_release_commit_message(&self) -> &str {
+        self.post_release_commit_message
             .as_deref()
-            .unwrap_or("chore: Release {{crate_name}} version {{version}}")
+            .unwrap_or_else(|| {
+                if self.consolidate_commits() {
+                    "chore: Start next development iteration"
+                } else {
+                    "chore: Start {{crate_name}} {{version}} development"
+                }
+            })
+    }
+
+    pub fn tag_message(&self) -> &str {
+        self.tag_message.as_deref().unwrap_or_else(|| {
+            if self.workspace_tag() {
+                "chore: Release version {{version}}"
+            } else {
+                "chore: Release {{crate_name}} version {{version}}"
+            }
+        })
     }
 
     pub fn tag_prefix(&self, is_root: bool) -> &str {
         // crate_name as default tag prefix for multi-crate project
         self.tag_prefix
             .as_deref()
-            .unwrap_or(if !is_root { "{{crate_name}}-" } else { "" })
+            .unwrap_or(if !is_root && !self.workspace_tag() {
+                "{{crate_name}}-"
+            } else {
+                ""
+            })
     }
 
     pub fn tag_name(&self) -> &str {
@@ -283,6 +455,11 @@ impl Config {
         self.tag.unwrap_or(true)
     }
 
+    /// Tag the release once for the whole workspace, rather than per crate
+    pub fn workspace_tag(&self) -> bool {
+        self.workspace_tag.unwrap_or(false)
+    }
+
     pub fn enable_features(&self) -> &[String] {
         self.enable_features
             .as_ref()
@@ -307,6 +484,21 @@ impl Config {
         self.dependent_version.unwrap_or_default()
     }
 
+    pub fn dependent_bump(&self) -> DependentBump {
+        self.dependent_bump.unwrap_or_default()
+    }
+
+    pub fn calver_format(&self) -> &str {
+        self.calver_format.as_deref().unwrap_or("YYYY.MM.MICRO")
+    }
+
+    /// Pre-release identifiers, from least to most stable
+    pub fn pre_release_channels(&self) -> &[String] {
+        self.pre_release_channels
+            .as_deref()
+            .unwrap_or(&DEFAULT_PRE_RELEASE_CHANNELS)
+    }
+
     pub fn metadata(&self) -> MetadataPolicy {
         self.metadata.unwrap_or_default()
     }
@@ -360,21 +552,86 @@ impl From<Vec<UnstableValues>> for Unstable {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Replace {
+    /// Path or glob, relative to the crate root
     pub file: PathBuf,
+    /// Globs of files to skip
+    #[serde(default)]
+    pub exclude: Vec<String>,
     pub search: String,
     pub replace: String,
     pub min: Option<usize>,
     pub max: Option<usize>,
     pub exactly: Option<usize>,
     #[serde(default)]
+    pub count: ReplaceCount,
+    #[serde(default)]
     pub prerelease: bool,
 }
 
+/// What `min`, `max`, and `exactly` of a [`Replace`] apply to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReplaceCount {
+    /// Each file matching `file`
+    #[default]
+    PerFile,
+    /// All files matching `file` together
+    Total,
+}
+
+/// Classify matching commits, overriding the Conventional Commit defaults
+///
+/// All of the specified fields must match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CommitRule {
+    /// Conventional Commit type, e.g. `deps`
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    /// Conventional Commit scope
+    pub scope: Option<String>,
+    /// Regex matched against the commit summary
+    pub summary: Option<String>,
+    /// Regex matched against each `Token: value` trailer of the commit message
+    pub trailer: Option<String>,
+    pub status: crate::steps::changes::CommitStatus,
+}
+
+/// A `[workspace-]<point>-hook` setting
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HookKey {
+    pub point: HookPoint,
+    /// Whether the hook runs once for the workspace rather than for each package
+    pub workspace: bool,
+}
+
+impl Serialize for HookKey {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let prefix = if self.workspace { "workspace-" } else { "" };
+        serializer.collect_str(&format_args!("{prefix}{}-hook", self.point.name()))
+    }
+}
+
+impl<'de> Deserialize<'de> for HookKey {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let key = String::deserialize(deserializer)?;
+        let (workspace, name) = match key.strip_prefix("workspace-") {
+            Some(name) => (true, name),
+            None => (false, key.as_str()),
+        };
+        name.strip_suffix("-hook")
+            .and_then(|name| HookPoint::ALL.into_iter().find(|p| p.name() == name))
+            .map(|point| Self { point, workspace })
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown field `{key}`")))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Command {
     Line(String),
     Args(Vec<String>),
+    Hook(HookCommand),
 }
 
 impl Command {
@@ -382,8 +639,42 @@ impl Command {
         match self {
             Self::Line(s) => vec![s.as_str()],
             Self::Args(a) => a.iter().map(|s| s.as_str()).collect(),
+            Self::Hook(h) => h.command.args(),
         }
     }
+
+    /// How long to wait for the command before killing it
+    pub fn timeout(&self) -> Option<std::time::Duration> {
+        match self {
+            Self::Line(_) | Self::Args(_) => None,
+            Self::Hook(h) => h.timeout.map(std::time::Duration::from_secs),
+        }
+    }
+
+    /// Times to run the command again after it failed
+    pub fn retries(&self) -> u32 {
+        match self {
+            Self::Line(_) | Self::Args(_) => 0,
+            Self::Hook(h) => h.retries.unwrap_or(0),
+        }
+    }
+
+    pub fn retry_delay(&self) -> std::time::Duration {
+        match self {
+            Self::Line(_) | Self::Args(_) => std::time::Duration::ZERO,
+            Self::Hook(h) => std::time::Duration::from_secs(h.retry_delay.unwrap_or(0)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "kebab-case")]
+pub struct HookCommand {
+    pub command: Box<Command>,
+    pub timeout: Option<u64>,
+    pub retries: Option<u32>,
+    pub retry_delay: Option<u64>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
@@ -398,6 +689,20 @@ pub enum DependentVersion {
     Fix,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+#[value(rename_all = "kebab-case")]
+#[derive(Default)]
+pub enum DependentBump {
+    /// Only update the requirement of dependents
+    #[default]
+    None,
+    /// Release affected dependents with a patch bump
+    Patch,
+    /// Release affected dependents with a breaking bump when their public dependency had one
+    Match,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
 #[serde(rename_all = "kebab-case")]
 #[value(rename_all = "kebab-case")]
@@ -446,6 +751,45 @@ impl SharedVersion {
     }
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+#[serde(rename_all = "kebab-case")]
+pub struct Group {
+    pub members: Vec<String>,
+    pub tag_name: Option<String>,
+    pub pre_release_commit_message: Option<String>,
+    pub shared_version: Option<bool>,
+}
+
+impl Group {
+    /// Match the package names in `members`
+    pub fn matcher(&self) -> CargoResult<globset::GlobSet> {
+        let mut builder = globset::GlobSetBuilder::new();
+        for member in &self.members {
+            let glob = globset::Glob::new(member)
+                .with_context(|| format!("invalid group member `{member}`"))?;
+            builder.add(glob);
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Group-level settings, layered between workspace and package config
+    pub fn to_config(&self, name: &str) -> Config {
+        Config {
+            tag_name: self.tag_name.clone(),
+            pre_release_commit_message: self.pre_release_commit_message.clone(),
+            shared_version: self.shared_version.map(|shared| {
+                if shared {
+                    SharedVersion::Name(name.to_owned())
+                } else {
+                    SharedVersion::Enabled(false)
+                }
+            }),
+            ..Default::default()
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(default)]
 struct CargoManifest {
@@ -527,6 +871,8 @@ pub struct RateLimit {
     pub new_packages: Option<usize>,
     #[serde(default)]
     pub existing_packages: Option<usize>,
+    #[serde(default)]
+    pub schedule: Option<bool>,
 }
 
 impl RateLimit {
@@ -538,6 +884,7 @@ impl RateLimit {
         Self {
             new_packages: Some(5),
             existing_packages: Some(30),
+            schedule: Some(false),
         }
     }
 
@@ -548,6 +895,9 @@ impl RateLimit {
         if source.existing_packages.is_some() {
             self.existing_packages = source.existing_packages;
         }
+        if source.schedule.is_some() {
+            self.schedule = source.schedule;
+        }
     }
 
     pub fn new_packages(&self) -> usize {
@@ -557,6 +907,12 @@ impl RateLimit {
     pub fn existing_packages(&self) -> usize {
         self.existing_packages.unwrap_or(30)
     }
+
+    /// Spread publishes out over time to stay within the rate limits, rather than refusing to
+    /// release
+    pub fn schedule(&self) -> bool {
+        self.schedule.unwrap_or(false)
+    }
 }
 
 pub fn load_workspace_config(
@@ -598,10 +954,13 @@ pub fn load_workspace_config(
     Ok(release_config)
 }
 
+/// Load the configuration of `pkg`, layering in the settings of its `group` from
+/// [`Config::group_members`]
 pub fn load_package_config(
     args: &ConfigArgs,
     ws_meta: &cargo_metadata::Metadata,
     pkg: &cargo_metadata::Package,
+    group: Option<(&str, &Group)>,
 ) -> CargoResult<Config> {
     let manifest_path = pkg.manifest_path.as_std_path();
 
@@ -611,8 +970,19 @@ pub fn load_package_config(
         ..Default::default()
     };
 
+    let group = group.map(|(name, group)| group.to_config(name));
+
     if !args.isolated {
-        let cfg = resolve_config(ws_meta.workspace_root.as_std_path(), manifest_path)?;
+        let cfg = resolve_workspace_config(ws_meta.workspace_root.as_std_path())?;
+        release_config.update(&cfg);
+    }
+
+    if let Some(group) = group {
+        release_config.update(&group);
+    }
+
+    if !args.isolated {
+        let cfg = resolve_crate_config(manifest_path)?;
         release_config.update(&cfg);
     }
 
@@ -654,6 +1024,10 @@ pub struct ConfigArgs {
     #[arg(long, value_name = "ACTION", value_enum)]
     pub dependent_version: Option<DependentVersion>,
 
+    /// Specify whether workspace dependents of this crate should be released with it.
+    #[arg(long, value_name = "POLICY", value_enum)]
+    pub dependent_bump: Option<DependentBump>,
+
     /// Comma-separated globs of branch names a release can happen from
     #[arg(long, value_delimiter = ',', value_name = "GLOB[,...]")]
     pub allow_branch: Option<Vec<String>>,
@@ -683,6 +1057,7 @@ impl ConfigArgs {
             sign_commit: self.sign(),
             sign_tag: self.sign(),
             dependent_version: self.dependent_version,
+            dependent_bump: self.dependent_bump,
             certs_source: self.certs_source,
             ..Default::default()
         };
@@ -959,8 +1334,18 @@ pub fn resolve_workspace_config(workspace_root: &Path) -> CargoResult<Config> {
 /// workspace.
 pub fn resolve_config(workspace_root: &Path, manifest_path: &Path) -> CargoResult<Config> {
     let mut config = resolve_workspace_config(workspace_root)?;
+    config.update(&resolve_crate_config(manifest_path)?);
+    Ok(config)
+}
+
+/// Try to resolve crate configuration source.
+///
+/// This tries the following sources in order, merging the results:
+/// 1. $(crate)/release.toml
+/// 2. $(crate)/Cargo.toml `package.metadata.release`
+fn resolve_crate_config(manifest_path: &Path) -> CargoResult<Config> {
+    let mut config = Config::default();
 
-    // Crate config
     let crate_root = manifest_path.parent().unwrap_or_else(|| Path::new("."));
     let default_config = crate_root.join("release.toml");
     let current_dir_config = get_config_from_file(&default_config)?;
@@ -1067,4 +1452,124 @@ mod test {
             assert!(!release_config.sign_commit());
         }
     }
+
+    mod command {
+        use super::*;
+
+        #[test]
+        fn hook_table() {
+            let config: Config = toml::from_str(
+                r#"
+pre-release-hook = ["echo", "a"]
+post-publish-hook = { command = ["echo", "b"], timeout = 5, retries = 2, retry-delay = 1 }
+"#,
+            )
+            .unwrap();
+            let pre = config.hook(HookPoint::PreRelease).unwrap();
+            assert_eq!(pre.args(), ["echo", "a"]);
+            assert_eq!(pre.timeout(), None);
+            assert_eq!(pre.retries(), 0);
+
+            let post = config.hook(HookPoint::PostPublish).unwrap();
+            assert_eq!(post.args(), ["echo", "b"]);
+            assert_eq!(post.timeout(), Some(std::time::Duration::from_secs(5)));
+            assert_eq!(post.retries(), 2);
+            assert_eq!(post.retry_delay(), std::time::Duration::from_secs(1));
+        }
+    }
+
+    mod hooks {
+        use super::*;
+
+        #[test]
+        fn keyed_by_point() {
+            let config: Config = toml::from_str(
+                r#"
+registry = "private"
+post-tag-hook = ["echo", "a"]
+workspace-post-tag-hook = ["echo", "b"]
+"#,
+            )
+            .unwrap();
+            assert_eq!(config.registry(), Some("private"));
+            assert_eq!(
+                config.hook(HookPoint::PostTag).unwrap().args(),
+                ["echo", "a"]
+            );
+            assert_eq!(
+                config.workspace_hook(HookPoint::PostTag).unwrap().args(),
+                ["echo", "b"]
+            );
+            assert!(config.hook(HookPoint::PostPush).is_none());
+
+            let mut merged = Config::new();
+            merged.update(&config);
+            assert_eq!(
+                merged.workspace_hook(HookPoint::PostTag).unwrap().args(),
+                ["echo", "b"]
+            );
+
+            let dumped = toml::to_string(&merged).unwrap();
+            assert!(dumped.contains("post-tag-hook = ["), "{dumped}");
+            assert!(dumped.contains("workspace-post-tag-hook = ["), "{dumped}");
+        }
+
+        #[test]
+        fn unknown_field() {
+            let err = toml::from_str::<Config>(r#"post-release-hook = ["echo"]"#)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("unknown field `post-release-hook`"), "{err}");
+
+            let err = toml::from_str::<Config>("sign-comit = true")
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("sign-comit"), "{err}");
+        }
+    }
+
+    mod group {
+        use super::*;
+
+        #[test]
+        fn members() {
+            let config: Config = toml::from_str(
+                r#"
+[groups.core]
+members = ["core", "core-*"]
+shared-version = true
+
+[groups.cli]
+members = ["cli"]
+"#,
+            )
+            .unwrap();
+            let members = config
+                .group_members(["core", "core-derive", "cli", "cli-test"])
+                .unwrap();
+            assert_eq!(members.get("core"), Some(&"core"));
+            assert_eq!(members.get("core-derive"), Some(&"core"));
+            assert_eq!(members.get("cli"), Some(&"cli"));
+            assert_eq!(members.get("cli-test"), None);
+
+            let group = config.groups()["core"].to_config("core");
+            assert_eq!(group.shared_version(), Some("core"));
+        }
+
+        #[test]
+        fn overlapping() {
+            let config: Config = toml::from_str(
+                r#"
+[groups.a]
+members = ["foo*"]
+
+[groups.b]
+members = ["foo-bar"]
+"#,
+            )
+            .unwrap();
+            assert!(config.group_members(["foo"]).unwrap().contains_key("foo"));
+            assert!(config.group_members(["foo", "foo-bar"]).is_err());
+        }
+    }
 }
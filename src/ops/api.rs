@@ -0,0 +1,643 @@
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+
+use crate::error::CargoResult;
+
+/// Public API of a crate, read from rustdoc's JSON output
+///
+/// Items are keyed by their kind and path, e.g. `function foo::bar::baz`, so they can be compared
+/// between builds where rustdoc's item ids differ.
+#[derive(Clone, Debug, Default)]
+pub struct Api {
+    version: Option<semver::Version>,
+    items: BTreeMap<String, Item>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Item {
+    signature: serde_json::Value,
+    /// Adding this item breaks downstream users, like a required trait method
+    required: bool,
+}
+
+impl Api {
+    pub fn from_rustdoc_json(json: &str) -> CargoResult<Self> {
+        let doc: serde_json::Value = serde_json::from_str(json)?;
+        let index = doc
+            .get("index")
+            .and_then(|i| i.as_object())
+            .ok_or_else(|| anyhow::format_err!("rustdoc JSON is missing its `index`"))?;
+        let root = doc
+            .get("root")
+            .and_then(|root| index.get(&id_key(root)?))
+            .ok_or_else(|| anyhow::format_err!("rustdoc JSON is missing its `root`"))?;
+        let crate_name = root
+            .get("name")
+            .and_then(|n| n.as_str())
+            .unwrap_or_default();
+
+        let mut walker = Walker {
+            index,
+            items: BTreeMap::new(),
+            visited: HashSet::new(),
+        };
+        walker.named(root, crate_name, false);
+        let version = doc
+            .get("crate_version")
+            .and_then(|v| v.as_str())
+            .and_then(|v| semver::Version::parse(v).ok());
+        Ok(Self {
+            version,
+            items: walker.items,
+        })
+    }
+
+    /// The documented version of the crate, if known
+    pub fn version(&self) -> Option<&semver::Version> {
+        self.version.as_ref()
+    }
+
+    /// Changes from `baseline` to `self`
+    pub fn diff(&self, baseline: &Self) -> Vec<Change> {
+        let removed = baseline
+            .items
+            .keys()
+            .filter(|path| !self.items.contains_key(*path))
+            .map(|path| Change::Removed(path.clone()));
+        let changed = self
+            .items
+            .iter()
+            .filter(|(path, item)| {
+                baseline
+                    .items
+                    .get(*path)
+                    .is_some_and(|b| b.signature != item.signature)
+            })
+            .map(|(path, _)| Change::Changed(path.clone()));
+        // Members of new items can't break anyone
+        let baseline_paths = baseline
+            .items
+            .keys()
+            .filter_map(|key| key.split_once(' ').map(|(_, path)| path))
+            .collect::<HashSet<_>>();
+        let added = self
+            .items
+            .iter()
+            .filter(|(path, _)| !baseline.items.contains_key(*path))
+            .map(|(path, item)| {
+                let parent = path
+                    .split_once(' ')
+                    .and_then(|(_, path)| path.rsplit_once("::"))
+                    .map(|(parent, _)| parent);
+                Change::Added {
+                    item: path.clone(),
+                    required: item.required && parent.is_some_and(|p| baseline_paths.contains(p)),
+                }
+            });
+        removed.chain(changed).chain(added).collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    Removed(String),
+    Changed(String),
+    Added { item: String, required: bool },
+}
+
+impl Change {
+    pub fn is_breaking(&self) -> bool {
+        match self {
+            Self::Removed(_) | Self::Changed(_) => true,
+            Self::Added { required, .. } => *required,
+        }
+    }
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Removed(item) => write!(f, "removed {item}"),
+            Self::Changed(item) => write!(f, "changed {item}"),
+            Self::Added {
+                item,
+                required: true,
+            } => write!(f, "added required {item}"),
+            Self::Added {
+                item,
+                required: false,
+            } => write!(f, "added {item}"),
+        }
+    }
+}
+
+/// Whether going from `baseline` to `planned` is a big enough bump for `changes`, following
+/// Cargo's semver conventions
+pub fn allows(baseline: &semver::Version, planned: &semver::Version, changes: &[Change]) -> bool {
+    if !baseline.pre.is_empty() {
+        // Anything goes between pre-releases
+        return true;
+    }
+
+    let baseline = (baseline.major, baseline.minor, baseline.patch);
+    let planned = (planned.major, planned.minor, planned.patch);
+    if changes.iter().any(Change::is_breaking) {
+        match baseline {
+            (0, 0, _) => baseline < planned,
+            (0, _, _) => (baseline.0, baseline.1) < (planned.0, planned.1),
+            (_, _, _) => baseline.0 < planned.0,
+        }
+    } else if !changes.is_empty() && baseline.0 != 0 {
+        (baseline.0, baseline.1) < (planned.0, planned.1)
+    } else {
+        true
+    }
+}
+
+struct Walker<'d> {
+    index: &'d serde_json::Map<String, serde_json::Value>,
+    items: BTreeMap<String, Item>,
+    visited: HashSet<String>,
+}
+
+impl<'d> Walker<'d> {
+    fn lookup(&self, id: &serde_json::Value) -> Option<&'d serde_json::Value> {
+        self.index.get(&id_key(id)?)
+    }
+
+    /// Record the item `id` that lives under `parent`
+    fn item(&mut self, id: &serde_json::Value, parent: &str, required: bool) {
+        let Some(item) = self.lookup(id) else {
+            return;
+        };
+        if !is_visible(item) {
+            return;
+        }
+        let Some((kind, inner)) = inner(item) else {
+            return;
+        };
+        match kind {
+            // Renamed to `use` in newer rustdoc versions
+            "use" | "import" => {
+                let name = inner.get("name").and_then(|n| n.as_str()).unwrap_or("*");
+                let target = inner.get("id").and_then(|id| self.lookup(id));
+                let is_glob = inner
+                    .get("is_glob")
+                    .or_else(|| inner.get("glob"))
+                    .and_then(|g| g.as_bool())
+                    .unwrap_or(false);
+                match target {
+                    Some(target) if is_glob => {
+                        if let Some((_, module)) = inner_of_kind(target, "module") {
+                            let target_id = inner.get("id").and_then(id_key).unwrap_or_default();
+                            if !self.visited.insert(format!("{parent}::*{target_id}")) {
+                                return;
+                            }
+                            for child in ids(module.get("items")) {
+                                self.item(child, parent, required);
+                            }
+                        }
+                    }
+                    Some(target) => {
+                        self.named(target, &format!("{parent}::{name}"), required);
+                    }
+                    None => {
+                        // Re-exported from another crate
+                        self.items.insert(
+                            format!("use {parent}::{name}"),
+                            Item {
+                                signature: inner.get("source").cloned().unwrap_or_default(),
+                                required,
+                            },
+                        );
+                    }
+                }
+            }
+            _ => {
+                let Some(name) = item.get("name").and_then(|n| n.as_str()) else {
+                    return;
+                };
+                self.named(item, &format!("{parent}::{name}"), required);
+            }
+        }
+    }
+
+    /// Record `item` as `path`, along with its members
+    fn named(&mut self, item: &'d serde_json::Value, path: &str, required: bool) {
+        let Some((kind, inner)) = inner(item) else {
+            return;
+        };
+        if !self.visited.insert(path.to_owned()) {
+            return;
+        }
+        let required = required
+            || match kind {
+                "function" => inner.get("has_body").and_then(|b| b.as_bool()) == Some(false),
+                "assoc_type" => inner.get("type").is_some_and(|t| t.is_null()),
+                "assoc_const" => inner.get("value").is_some_and(|v| v.is_null()),
+                _ => false,
+            };
+        self.items.insert(
+            format!("{kind} {path}"),
+            Item {
+                signature: signature(inner),
+                required,
+            },
+        );
+
+        let exhaustive = !is_non_exhaustive(item);
+        match kind {
+            "module" => {
+                for child in ids(inner.get("items")) {
+                    self.item(child, path, false);
+                }
+            }
+            "struct" | "union" => {
+                let fields = inner.get("kind").unwrap_or(inner);
+                let constructible = exhaustive
+                    && !fields
+                        .pointer("/plain/has_stripped_fields")
+                        .and_then(|s| s.as_bool())
+                        .unwrap_or(false);
+                for field in member_ids(fields) {
+                    self.item(field, path, constructible);
+                }
+            }
+            "enum" => {
+                for variant in ids(inner.get("variants")) {
+                    self.item(variant, path, exhaustive);
+                }
+            }
+            "variant" => {
+                for field in member_ids(inner.get("kind").unwrap_or(inner)) {
+                    self.item(field, path, exhaustive);
+                }
+            }
+            "trait" => {
+                for child in ids(inner.get("items")) {
+                    self.item(child, path, false);
+                }
+            }
+            _ => {}
+        }
+        for impl_id in ids(inner.get("impls")) {
+            self.impl_block(impl_id, path);
+        }
+    }
+
+    fn impl_block(&mut self, id: &serde_json::Value, self_path: &str) {
+        let Some((_, inner)) = self.lookup(id).and_then(|i| inner_of_kind(i, "impl")) else {
+            return;
+        };
+        if inner.get("blanket_impl").is_some_and(|b| !b.is_null()) {
+            // Comes from the trait's crate, not ours
+            return;
+        }
+        match inner.get("trait").filter(|t| !t.is_null()) {
+            Some(trait_) => {
+                let trait_name = trait_
+                    .get("path")
+                    .or_else(|| trait_.get("name"))
+                    .and_then(|p| p.as_str())
+                    .unwrap_or_default();
+                let negative = inner
+                    .get("is_negative")
+                    .or_else(|| inner.get("negative"))
+                    .and_then(|n| n.as_bool())
+                    .unwrap_or(false);
+                let bang = if negative { "!" } else { "" };
+                self.items.insert(
+                    format!("impl {self_path}: {bang}{trait_name}"),
+                    Item {
+                        signature: signature(inner),
+                        required: false,
+                    },
+                );
+            }
+            None => {
+                for child in ids(inner.get("items")) {
+                    self.item(child, self_path, false);
+                }
+            }
+        }
+    }
+}
+
+/// `index` key for an item id, which is a number or, in older rustdoc versions, a string
+fn id_key(id: &serde_json::Value) -> Option<String> {
+    match id {
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::String(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+fn is_visible(item: &serde_json::Value) -> bool {
+    // Private items are stripped from the output; `default` covers enum variants and trait items
+    matches!(
+        item.get("visibility").and_then(|v| v.as_str()),
+        Some("public" | "default")
+    )
+}
+
+fn is_non_exhaustive(item: &serde_json::Value) -> bool {
+    item.get("attrs")
+        .and_then(|a| a.as_array())
+        .into_iter()
+        .flatten()
+        .any(|attr| attr.to_string().contains("non_exhaustive"))
+}
+
+/// The item's kind along with its kind-specific details
+fn inner(item: &serde_json::Value) -> Option<(&str, &serde_json::Value)> {
+    let inner = item.get("inner")?.as_object()?;
+    if inner.len() != 1 {
+        return None;
+    }
+    inner.iter().next().map(|(k, v)| (k.as_str(), v))
+}
+
+fn inner_of_kind<'i>(
+    item: &'i serde_json::Value,
+    kind: &str,
+) -> Option<(&'i str, &'i serde_json::Value)> {
+    inner(item).filter(|(k, _)| *k == kind)
+}
+
+fn ids(list: Option<&serde_json::Value>) -> impl Iterator<Item = &serde_json::Value> {
+    list.and_then(|l| l.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|id| !id.is_null())
+}
+
+/// Fields of a struct or variant, whether plain (`{ a: T }`) or tuple (`(T)`)
+fn member_ids(kind: &serde_json::Value) -> impl Iterator<Item = &serde_json::Value> {
+    let fields = kind
+        .pointer("/plain/fields")
+        .or_else(|| kind.pointer("/struct/fields"))
+        .or_else(|| kind.get("fields"))
+        .or_else(|| kind.get("tuple"));
+    ids(fields)
+}
+
+/// `inner` without anything that is compared separately or changes between builds
+fn signature(inner: &serde_json::Value) -> serde_json::Value {
+    const SKIP: &[&str] = &[
+        // Members, compared as items of their own
+        "items",
+        "impls",
+        "implementations",
+        "fields",
+        "variants",
+        "provided_trait_methods",
+        // Build-specific
+        "id",
+        "is_stripped",
+        // Values, rather than the API
+        "expr",
+        "value",
+        "is_literal",
+    ];
+
+    match inner {
+        serde_json::Value::Object(map) => map
+            .iter()
+            .filter(|(k, _)| !SKIP.contains(&k.as_str()))
+            .map(|(k, v)| (k.clone(), signature(v)))
+            .collect::<serde_json::Map<_, _>>()
+            .into(),
+        serde_json::Value::Array(list) if list.iter().all(|v| !v.is_object() && !v.is_array()) => {
+            // Lists of ids, like tuple fields, where only the presence of an item matters
+            list.iter()
+                .map(|v| serde_json::Value::Bool(!v.is_null()))
+                .collect::<Vec<_>>()
+                .into()
+        }
+        serde_json::Value::Array(list) => list.iter().map(signature).collect::<Vec<_>>().into(),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A crate with the given items in its root module
+    fn api(index: serde_json::Value, root_items: &[u64]) -> Api {
+        let mut index = index.as_object().unwrap().clone();
+        index.insert(
+            "0".to_owned(),
+            serde_json::json!({
+                "name": "foo",
+                "visibility": "public",
+                "attrs": [],
+                "inner": {"module": {"is_crate": true, "items": root_items, "is_stripped": false}},
+            }),
+        );
+        let doc = serde_json::json!({"root": 0, "index": index, "format_version": 57});
+        Api::from_rustdoc_json(&doc.to_string()).unwrap()
+    }
+
+    fn function(name: &str, output: &str, has_body: bool) -> serde_json::Value {
+        serde_json::json!({
+            "name": name,
+            "visibility": "public",
+            "attrs": [],
+            "inner": {"function": {
+                "sig": {"inputs": [], "output": {"primitive": output}, "is_c_variadic": false},
+                "generics": {"params": [], "where_predicates": []},
+                "has_body": has_body,
+            }},
+        })
+    }
+
+    fn unit_enum(name: &str, variants: &[u64], attrs: &[&str]) -> serde_json::Value {
+        serde_json::json!({
+            "name": name,
+            "visibility": "public",
+            "attrs": attrs,
+            "inner": {"enum": {
+                "generics": {"params": [], "where_predicates": []},
+                "has_stripped_variants": false,
+                "variants": variants,
+                "impls": [],
+            }},
+        })
+    }
+
+    fn variant(name: &str) -> serde_json::Value {
+        serde_json::json!({
+            "name": name,
+            "visibility": "default",
+            "attrs": [],
+            "inner": {"variant": {"kind": "plain", "discriminant": null}},
+        })
+    }
+
+    fn diff(baseline: &Api, current: &Api) -> Vec<String> {
+        current
+            .diff(baseline)
+            .iter()
+            .map(|c| c.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn unchanged() {
+        let baseline = api(serde_json::json!({"1": function("f", "u32", true)}), &[1]);
+        // Ids are not stable between builds
+        let current = api(serde_json::json!({"7": function("f", "u32", true)}), &[7]);
+        assert_eq!(diff(&baseline, &current), Vec::<String>::new());
+    }
+
+    #[test]
+    fn functions() {
+        let baseline = api(
+            serde_json::json!({
+                "1": function("f", "u32", true),
+                "2": function("g", "u32", true),
+            }),
+            &[1, 2],
+        );
+        let current = api(
+            serde_json::json!({
+                "1": function("f", "u64", true),
+                "3": function("h", "u32", true),
+            }),
+            &[1, 3],
+        );
+        let changes = current.diff(&baseline);
+        assert_eq!(
+            changes.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+            [
+                "removed function foo::g",
+                "changed function foo::f",
+                "added function foo::h",
+            ]
+        );
+        assert!(
+            changes
+                .iter()
+                .all(|c| c.is_breaking() != c.to_string().starts_with("added"))
+        );
+    }
+
+    #[test]
+    fn re_export() {
+        let baseline = api(serde_json::json!({"1": function("f", "u32", true)}), &[1]);
+        let current = api(
+            serde_json::json!({
+                "1": function("f", "u32", true),
+                "2": {
+                    "name": "m",
+                    "visibility": "public",
+                    "attrs": [],
+                    "inner": {"module": {"is_crate": false, "items": [], "is_stripped": false}},
+                },
+                "3": {
+                    "name": null,
+                    "visibility": "public",
+                    "attrs": [],
+                    "inner": {"use": {"source": "m::f", "name": "f", "id": 1, "is_glob": false}},
+                },
+            }),
+            &[2, 3],
+        );
+        assert_eq!(diff(&baseline, &current), ["added module foo::m"]);
+    }
+
+    #[test]
+    fn enum_variants() {
+        let baseline = api(
+            serde_json::json!({
+                "1": unit_enum("E", &[2], &[]),
+                "2": variant("A"),
+                "3": unit_enum("N", &[4], &["#[non_exhaustive]"]),
+                "4": variant("A"),
+            }),
+            &[1, 3],
+        );
+        let current = api(
+            serde_json::json!({
+                "1": unit_enum("E", &[2, 5], &[]),
+                "2": variant("A"),
+                "5": variant("B"),
+                "3": unit_enum("N", &[4, 6], &["#[non_exhaustive]"]),
+                "4": variant("A"),
+                "6": variant("B"),
+                "7": unit_enum("X", &[8], &[]),
+                "8": variant("A"),
+            }),
+            &[1, 3, 7],
+        );
+        assert_eq!(
+            diff(&baseline, &current),
+            [
+                "added enum foo::X",
+                "added required variant foo::E::B",
+                "added variant foo::N::B",
+                "added variant foo::X::A",
+            ]
+        );
+    }
+
+    #[test]
+    fn required_trait_items() {
+        let trait_ = |items: &[u64]| {
+            serde_json::json!({
+                "name": "T",
+                "visibility": "public",
+                "attrs": [],
+                "inner": {"trait": {
+                    "is_auto": false,
+                    "is_unsafe": false,
+                    "items": items,
+                    "generics": {"params": [], "where_predicates": []},
+                    "bounds": [],
+                    "implementations": [],
+                }},
+            })
+        };
+        let baseline = api(serde_json::json!({"1": trait_(&[])}), &[1]);
+        let current = api(
+            serde_json::json!({
+                "1": trait_(&[2, 3]),
+                "2": function("provided", "u32", true),
+                "3": function("required", "u32", false),
+            }),
+            &[1],
+        );
+        assert_eq!(
+            diff(&baseline, &current),
+            [
+                "added function foo::T::provided",
+                "added required function foo::T::required",
+            ]
+        );
+    }
+
+    #[test]
+    fn allowed_bumps() {
+        let v = |v| semver::Version::parse(v).unwrap();
+        let breaking = [Change::Removed("function foo::f".to_owned())];
+        let addition = [Change::Added {
+            item: "function foo::f".to_owned(),
+            required: false,
+        }];
+
+        assert!(allows(&v("1.2.3"), &v("1.2.4"), &[]));
+        assert!(!allows(&v("1.2.3"), &v("1.2.4"), &addition));
+        assert!(allows(&v("1.2.3"), &v("1.3.0"), &addition));
+        assert!(!allows(&v("1.2.3"), &v("1.3.0"), &breaking));
+        assert!(allows(&v("1.2.3"), &v("2.0.0"), &breaking));
+        assert!(allows(&v("1.2.3"), &v("2.0.0-rc.1"), &breaking));
+
+        assert!(allows(&v("0.3.1"), &v("0.3.2"), &addition));
+        assert!(!allows(&v("0.3.1"), &v("0.3.2"), &breaking));
+        assert!(allows(&v("0.3.1"), &v("0.4.0"), &breaking));
+        assert!(allows(&v("0.0.3"), &v("0.0.4"), &breaking));
+
+        assert!(allows(&v("2.0.0-rc.1"), &v("2.0.0"), &breaking));
+    }
+}
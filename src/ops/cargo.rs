@@ -6,7 +6,7 @@ use itertools::Itertools as _;
 
 use crate::config::{self, CertsSource};
 use crate::error::CargoResult;
-use crate::ops::cmd::call;
+use crate::ops::cmd::{Output, call, call_with_env, call_with_timeout};
 
 /// Expresses what features flags should be used
 #[derive(Clone, Debug)]
@@ -63,6 +63,50 @@ pub fn publish(
         return Ok(true);
     }
 
+    let command = publish_command(
+        dry_run,
+        verify,
+        manifest_path,
+        pkgids,
+        features,
+        registry,
+        target,
+    );
+    call(command, false)
+}
+
+/// Like [`publish`] for a single package but also returns cargo's output, e.g. to look for rate
+/// limit errors
+pub fn publish_capture_output(
+    dry_run: bool,
+    verify: bool,
+    manifest_path: &Path,
+    pkgid: &str,
+    features: &[&Features],
+    registry: Option<&str>,
+    target: Option<&str>,
+) -> CargoResult<Output> {
+    let command = publish_command(
+        dry_run,
+        verify,
+        manifest_path,
+        &[pkgid],
+        features,
+        registry,
+        target,
+    );
+    call_with_timeout(command, Default::default(), None, None, true)
+}
+
+fn publish_command(
+    dry_run: bool,
+    verify: bool,
+    manifest_path: &Path,
+    pkgids: &[&str],
+    features: &[&Features],
+    registry: Option<&str>,
+    target: Option<&str>,
+) -> Vec<String> {
     let cargo = cargo();
 
     let mut command: Vec<&str> = vec![
@@ -118,7 +162,138 @@ pub fn publish(
         command.push(&selective);
     }
 
-    call(command, false)
+    command.into_iter().map(ToOwned::to_owned).collect()
+}
+
+/// The variable and flags for rustdoc's JSON output, keeping any flags the user set
+fn json_rustdocflags() -> (&'static str, std::ffi::OsString) {
+    const FLAGS: [&str; 4] = ["-Z", "unstable-options", "--output-format", "json"];
+    // Cargo ignores `RUSTDOCFLAGS` when the encoded variant is set
+    if let Some(existing) = env::var_os("CARGO_ENCODED_RUSTDOCFLAGS") {
+        (
+            "CARGO_ENCODED_RUSTDOCFLAGS",
+            append_flags(existing, "\x1f", &FLAGS),
+        )
+    } else {
+        let existing = env::var_os("RUSTDOCFLAGS").unwrap_or_default();
+        ("RUSTDOCFLAGS", append_flags(existing, " ", &FLAGS))
+    }
+}
+
+fn append_flags(
+    mut existing: std::ffi::OsString,
+    separator: &str,
+    flags: &[&str],
+) -> std::ffi::OsString {
+    for flag in flags {
+        if !existing.is_empty() {
+            existing.push(separator);
+        }
+        existing.push(flag);
+    }
+    existing
+}
+
+/// Build rustdoc's JSON output for the library of `pkgid`, returning its contents
+pub fn rustdoc_json(
+    manifest_path: &Path,
+    pkgid: &str,
+    lib_name: &str,
+    target_dir: &Path,
+) -> CargoResult<String> {
+    let cargo = cargo();
+    let command = vec![
+        cargo.as_str(),
+        "doc",
+        "--quiet",
+        "--no-deps",
+        "--lib",
+        "--manifest-path",
+        manifest_path.to_str().unwrap(),
+        "--package",
+        pkgid,
+        "--target-dir",
+        target_dir.to_str().unwrap(),
+    ];
+    // rustdoc's JSON output is unstable
+    let (flags_var, flags) = json_rustdocflags();
+    let envs = std::collections::BTreeMap::from([
+        (
+            std::ffi::OsStr::new("RUSTC_BOOTSTRAP"),
+            std::ffi::OsStr::new("1"),
+        ),
+        (std::ffi::OsStr::new(flags_var), flags.as_os_str()),
+    ]);
+    let manifest_root = manifest_path
+        .parent()
+        .expect("always at least a parent dir");
+    if !call_with_env(command, envs, manifest_root, false)? {
+        anyhow::bail!("failed to document {pkgid}");
+    }
+
+    let json_path = target_dir
+        .join("doc")
+        .join(format!("{}.json", lib_name.replace('-', "_")));
+    std::fs::read_to_string(&json_path)
+        .map_err(|e| anyhow::format_err!("failed to read {}: {e}", json_path.display()))
+}
+
+/// Build rustdoc's JSON output for `name` `version` as published to `registry`
+pub fn registry_rustdoc_json(
+    name: &str,
+    version: &semver::Version,
+    registry: Option<&str>,
+    lib_name: &str,
+    target_dir: &Path,
+) -> CargoResult<String> {
+    let project = target_dir.join("registry-baseline");
+    std::fs::create_dir_all(project.join("src"))?;
+
+    let mut dependency = toml_edit::InlineTable::new();
+    dependency.insert("version", format!("={version}").into());
+    if let Some(registry) = registry {
+        dependency.insert("registry", registry.into());
+    }
+    let mut manifest: toml_edit::DocumentMut = r#"
+[package]
+name = "release-semver-baseline"
+version = "0.0.0"
+edition = "2021"
+publish = false
+
+[workspace]
+
+[dependencies]
+"#
+    .parse()
+    .expect("valid manifest");
+    manifest["dependencies"][name] = toml_edit::value(dependency);
+    let manifest_path = project.join("Cargo.toml");
+    std::fs::write(&manifest_path, manifest.to_string())?;
+    std::fs::write(project.join("src").join("lib.rs"), "")?;
+
+    rustdoc_json(
+        &manifest_path,
+        &format!("{name}@{version}"),
+        lib_name,
+        target_dir,
+    )
+}
+
+/// Whether any version of `name` is in the registry's index, assuming not if it can't be read
+pub fn crate_exists(
+    index: &mut crate::ops::index::CratesIoIndex,
+    registry: Option<&str>,
+    name: &str,
+    certs_source: CertsSource,
+) -> bool {
+    match index.has_krate(registry, name, certs_source) {
+        Ok(has_krate) => has_krate,
+        Err(err) => {
+            log::warn!("failed to read metadata for {name}: {err:#}");
+            false
+        }
+    }
 }
 
 pub fn is_published(
@@ -424,6 +599,32 @@ fn upgrade_req(
     true
 }
 
+/// Whether `manifest_path` declares its normal dependency on `name` as `public`
+///
+/// Returns `None` when this isn't declared.
+pub fn is_public_dependency(manifest_path: &Path, name: &str) -> CargoResult<Option<bool>> {
+    let manifest = std::fs::read_to_string(manifest_path)?;
+    let manifest: toml_edit::DocumentMut = manifest.parse()?;
+
+    let targets = manifest
+        .get("target")
+        .and_then(|t| t.as_table_like())
+        .into_iter()
+        .flat_map(|t| t.iter().filter_map(|(_, t)| t.as_table_like()));
+    let public = std::iter::once(manifest.as_table() as &dyn toml_edit::TableLike)
+        .chain(targets)
+        .filter_map(|t| t.get("dependencies").and_then(|d| d.as_table_like()))
+        .flat_map(|d| d.iter())
+        .filter_map(|(key, dep)| {
+            let dep = dep.as_table_like()?;
+            let dep_name = dep.get("package").and_then(|p| p.as_str()).unwrap_or(key);
+            (dep_name == name).then(|| dep.get("public").and_then(|p| p.as_bool()))?
+        })
+        .reduce(|lhs, rhs| lhs || rhs);
+
+    Ok(public)
+}
+
 pub fn update_lock(manifest_path: &Path) -> CargoResult<()> {
     cargo_metadata::MetadataCommand::new()
         .manifest_path(manifest_path)
@@ -516,6 +717,32 @@ mod test {
     use assert_fs::prelude::*;
     use predicates::prelude::*;
 
+    mod append_flags {
+        use super::*;
+
+        const FLAGS: [&str; 2] = ["-Z", "unstable-options"];
+
+        #[test]
+        fn empty() {
+            assert_eq!(
+                append_flags(Default::default(), " ", &FLAGS),
+                "-Z unstable-options"
+            );
+        }
+
+        #[test]
+        fn existing() {
+            assert_eq!(
+                append_flags("--cfg docsrs".into(), " ", &FLAGS),
+                "--cfg docsrs -Z unstable-options"
+            );
+            assert_eq!(
+                append_flags("--cfg\x1fdocsrs".into(), "\x1f", &FLAGS),
+                "--cfg\x1fdocsrs\x1f-Z\x1funstable-options"
+            );
+        }
+    }
+
     mod set_package_version {
         use super::*;
 
@@ -644,4 +871,67 @@ mod test {
             temp.close().unwrap();
         }
     }
+    mod is_public_dependency {
+        use super::*;
+
+        #[test]
+        fn declared() {
+            let temp = assert_fs::TempDir::new().unwrap();
+            let manifest_path = temp.child("Cargo.toml");
+            manifest_path
+                .write_str(
+                    r#"
+[package]
+name = "bar"
+version = "0.1.0"
+
+[dependencies]
+foo = { version = "0.3", path = "../foo", public = true }
+baz = { version = "1", path = "../baz", public = false }
+renamed = { version = "1", path = "../qux", package = "qux", public = true }
+unspecified = { version = "1", path = "../unspecified" }
+
+[target.'cfg(unix)'.dependencies]
+baz = { version = "1", path = "../baz" }
+
+[dev-dependencies]
+dev = { version = "1", path = "../dev", public = true }
+"#,
+                )
+                .unwrap();
+
+            let public = |name| is_public_dependency(manifest_path.path(), name).unwrap();
+            assert_eq!(public("foo"), Some(true));
+            assert_eq!(public("baz"), Some(false));
+            assert_eq!(public("qux"), Some(true));
+            assert_eq!(public("renamed"), None);
+            assert_eq!(public("unspecified"), None);
+            assert_eq!(public("dev"), None);
+
+            temp.close().unwrap();
+        }
+    }
+
+    mod unreadable_index {
+        use super::*;
+
+        #[test]
+        fn unknown_registry() {
+            let mut index = crate::ops::index::CratesIoIndex::new();
+            let registry = Some("cargo-release-test-unknown-registry");
+            assert!(!is_published(
+                &mut index,
+                registry,
+                "foo",
+                "0.1.0",
+                CertsSource::Webpki
+            ));
+            assert!(!crate_exists(
+                &mut index,
+                registry,
+                "foo",
+                CertsSource::Webpki
+            ));
+        }
+    }
 }
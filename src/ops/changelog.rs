@@ -0,0 +1,174 @@
+use crate::error::CargoResult;
+
+/// Kind of change, in the order sections are rendered
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Group {
+    Breaking,
+    Features,
+    Fixes,
+    Performance,
+    Documentation,
+    Other,
+}
+
+impl Group {
+    fn heading(self) -> &'static str {
+        match self {
+            Self::Breaking => "Breaking Changes",
+            Self::Features => "Features",
+            Self::Fixes => "Fixes",
+            Self::Performance => "Performance",
+            Self::Documentation => "Documentation",
+            Self::Other => "Other",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Entry<'a> {
+    pub group: Group,
+    pub scope: Option<&'a str>,
+    pub description: &'a str,
+}
+
+/// Render a [Keep a Changelog](https://keepachangelog.com) section for `version`
+pub fn render_section(version: &str, date: &str, entries: &[Entry<'_>]) -> String {
+    let mut entries = entries.to_vec();
+    // Stable sort to preserve the commit order within a group
+    entries.sort_by_key(|e| e.group);
+
+    let mut section = format!("## [{version}] - {date}\n");
+    let mut current = None;
+    for entry in &entries {
+        if current != Some(entry.group) {
+            current = Some(entry.group);
+            section.push_str(&format!("\n### {}\n\n", entry.group.heading()));
+        }
+        if let Some(scope) = entry.scope {
+            section.push_str(&format!("- **{scope}:** {}\n", entry.description));
+        } else {
+            section.push_str(&format!("- {}\n", entry.description));
+        }
+    }
+    section
+}
+
+/// Insert `section` before the most recent release, leaving `Unreleased` in place
+pub fn insert_section(changelog: &str, section: &str) -> CargoResult<String> {
+    let mut offset = 0;
+    let mut insert_at = None;
+    for line in changelog.split_inclusive('\n') {
+        if let Some(heading) = line.strip_prefix("## ") {
+            let heading = heading.trim().trim_start_matches('[');
+            if !heading.to_ascii_lowercase().starts_with("unreleased") {
+                insert_at = Some(offset);
+                break;
+            }
+        }
+        offset += line.len();
+    }
+
+    let mut updated = String::with_capacity(changelog.len() + section.len() + 2);
+    match insert_at {
+        Some(insert_at) => {
+            updated.push_str(&changelog[..insert_at]);
+            updated.push_str(section);
+            updated.push('\n');
+            updated.push_str(&changelog[insert_at..]);
+        }
+        None => {
+            if changelog.trim().is_empty() {
+                anyhow::bail!("changelog is empty, expected at least a title");
+            }
+            updated.push_str(changelog.trim_end());
+            updated.push_str("\n\n");
+            updated.push_str(section);
+        }
+    }
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn render_groups_in_order() {
+        let entries = [
+            Entry {
+                group: Group::Fixes,
+                scope: None,
+                description: "Don't panic",
+            },
+            Entry {
+                group: Group::Features,
+                scope: Some("cli"),
+                description: "Add `--foo`",
+            },
+            Entry {
+                group: Group::Breaking,
+                scope: None,
+                description: "Remove `bar`",
+            },
+            Entry {
+                group: Group::Fixes,
+                scope: None,
+                description: "Handle empty input",
+            },
+        ];
+        let actual = render_section("1.0.0", "2023-01-01", &entries);
+        assert_eq!(
+            actual,
+            "## [1.0.0] - 2023-01-01
+
+### Breaking Changes
+
+- Remove `bar`
+
+### Features
+
+- **cli:** Add `--foo`
+
+### Fixes
+
+- Don't panic
+- Handle empty input
+"
+        );
+    }
+
+    #[test]
+    fn insert_after_unreleased() {
+        let changelog = "# Changelog
+
+## [Unreleased] - ReleaseDate
+
+## [0.1.0] - 2022-01-01
+
+- Initial release
+";
+        let actual = insert_section(changelog, "## [0.2.0] - 2023-01-01\n\n- Fix\n").unwrap();
+        assert_eq!(
+            actual,
+            "# Changelog
+
+## [Unreleased] - ReleaseDate
+
+## [0.2.0] - 2023-01-01
+
+- Fix
+
+## [0.1.0] - 2022-01-01
+
+- Initial release
+"
+        );
+    }
+
+    #[test]
+    fn insert_first_release() {
+        let changelog = "# Changelog\n";
+        let actual = insert_section(changelog, "## [0.1.0] - 2023-01-01\n").unwrap();
+        assert_eq!(actual, "# Changelog\n\n## [0.1.0] - 2023-01-01\n");
+    }
+}
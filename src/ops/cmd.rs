@@ -11,68 +11,265 @@ fn do_call(
     envs: Option<BTreeMap<&OsStr, &OsStr>>,
     dry_run: bool,
 ) -> CargoResult<bool> {
+    if dry_run {
+        let command: Vec<_> = command.into_iter().map(|s| s.into()).collect();
+        if let Some(path) = path {
+            log::trace!("cd {}", path.display());
+        }
+        log::trace!("{}", command.join(" "));
+        return Ok(true);
+    }
+
+    let output = call_with_timeout(command, envs.unwrap_or_default(), path, None, false)?;
+    Ok(output.success())
+}
+
+pub fn call(
+    command: impl IntoIterator<Item = impl Into<String>>,
+    dry_run: bool,
+) -> CargoResult<bool> {
+    do_call(command, None, None, dry_run)
+}
+
+pub fn call_on_path(
+    command: impl IntoIterator<Item = impl Into<String>>,
+    path: &Path,
+    dry_run: bool,
+) -> CargoResult<bool> {
+    do_call(command, Some(path), None, dry_run)
+}
+
+pub fn call_with_env(
+    command: impl IntoIterator<Item = impl Into<String>>,
+    envs: BTreeMap<&OsStr, &OsStr>,
+    path: &Path,
+    dry_run: bool,
+) -> CargoResult<bool> {
+    do_call(command, Some(path), Some(envs), dry_run)
+}
+
+/// Result of [`call_with_timeout`]
+pub struct Output {
+    pub status: Option<std::process::ExitStatus>,
+    pub timed_out: bool,
+    /// Interleaved stdout and stderr
+    pub output: String,
+}
+
+impl Output {
+    pub fn success(&self) -> bool {
+        !self.timed_out && self.status.is_some_and(|s| s.success())
+    }
+}
+
+/// Run `command`, echoing its output while also capturing it when `capture` or a `timeout` is set
+///
+/// With a `timeout`, the command is killed when the timeout elapses.  On unix, it runs in its own
+/// process group so everything it started is killed with it.  Otherwise, without `capture`, the
+/// command inherits stdio and [`Output::output`] is empty.
+pub fn call_with_timeout(
+    command: impl IntoIterator<Item = impl Into<String>>,
+    envs: BTreeMap<&OsStr, &OsStr>,
+    path: Option<&Path>,
+    timeout: Option<std::time::Duration>,
+    capture: bool,
+) -> CargoResult<Output> {
+    use std::sync::{Arc, Mutex};
+
     let command: Vec<_> = command.into_iter().map(|s| s.into()).collect();
     if let Some(path) = path {
         log::trace!("cd {}", path.display());
     }
     log::trace!("{}", command.join(" "));
-    if dry_run {
-        return Ok(true);
-    }
     let mut iter = command.iter();
     let cmd_name = iter.next().unwrap();
 
     let mut cmd = Command::new(cmd_name);
-
-    if let Some(p) = path {
-        cmd.current_dir(p);
-    }
-
-    if let Some(e) = envs {
-        cmd.envs(e.iter());
+    if let Some(path) = path {
+        cmd.current_dir(path);
     }
-
+    cmd.envs(envs.iter());
     for arg in iter {
         if !arg.is_empty() {
             cmd.arg(arg);
         }
     }
+    if capture || timeout.is_some() {
+        cmd.stdout(std::process::Stdio::piped());
+        cmd.stderr(std::process::Stdio::piped());
+    }
+    #[cfg(unix)]
+    if timeout.is_some() {
+        use std::os::unix::process::CommandExt as _;
+        cmd.process_group(0);
+    }
 
     let ctx_dir = match path {
         Some(p) => format!(" within {}", p.display()),
         None => String::new(),
     };
-
     let mut child = cmd
         .spawn()
         .map_err(|e| anyhow::format_err!("failed to launch `{cmd_name}`{ctx_dir}: {e}"))?;
-    let result = child
-        .wait()
-        .map_err(|e| anyhow::format_err!("failed to launch `{cmd_name}`{ctx_dir}: {e}"))?;
 
-    Ok(result.success())
+    let captured = Arc::new(Mutex::new(Vec::new()));
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(tee(stdout, std::io::stdout(), captured.clone()));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(tee(stderr, std::io::stderr(), captured.clone()));
+    }
+
+    let deadline = timeout.map(|timeout| std::time::Instant::now() + timeout);
+    let mut timed_out = false;
+    let status = loop {
+        let status = child
+            .try_wait()
+            .map_err(|e| anyhow::format_err!("failed to launch `{cmd_name}`{ctx_dir}: {e}"))?;
+        if let Some(status) = status {
+            break Some(status);
+        }
+        if deadline.is_some_and(|deadline| deadline <= std::time::Instant::now()) {
+            timed_out = true;
+            kill(&mut child);
+            break child.wait().ok();
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    };
+    // Processes left running in the background can hold the pipes open indefinitely, so only give
+    // the readers a moment to drain what was already written
+    let drain_deadline = std::time::Instant::now() + READER_DRAIN;
+    while readers.iter().any(|reader| !reader.is_finished())
+        && std::time::Instant::now() < drain_deadline
+    {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    for reader in readers {
+        if reader.is_finished() {
+            let _ = reader.join();
+        }
+    }
+
+    let output = String::from_utf8_lossy(&captured.lock().unwrap()).into_owned();
+    Ok(Output {
+        status,
+        timed_out,
+        output,
+    })
 }
 
-pub fn call(
-    command: impl IntoIterator<Item = impl Into<String>>,
-    dry_run: bool,
-) -> CargoResult<bool> {
-    do_call(command, None, None, dry_run)
+/// How long to wait on output after the command exits
+const READER_DRAIN: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Copy `source` to `sink` as it comes in, keeping a copy in `captured`
+fn tee(
+    mut source: impl std::io::Read + Send + 'static,
+    mut sink: impl std::io::Write + Send + 'static,
+    captured: std::sync::Arc<std::sync::Mutex<Vec<u8>>>,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        let mut buffer = [0; 8192];
+        while let Ok(len) = source.read(&mut buffer) {
+            if len == 0 {
+                break;
+            }
+            let _ = sink.write_all(&buffer[..len]);
+            let _ = sink.flush();
+            captured.lock().unwrap().extend_from_slice(&buffer[..len]);
+        }
+    })
 }
 
-pub fn call_on_path(
-    command: impl IntoIterator<Item = impl Into<String>>,
-    path: &Path,
-    dry_run: bool,
-) -> CargoResult<bool> {
-    do_call(command, Some(path), None, dry_run)
+/// Kill `child` along with anything it started in its process group
+///
+/// Without process groups, only `child` itself is killed.
+fn kill(child: &mut std::process::Child) {
+    #[cfg(unix)]
+    if let Ok(pid) = libc::pid_t::try_from(child.id()) {
+        // SAFETY: `kill` has no memory safety requirements; the negated pid addresses the process
+        // group `child` leads
+        unsafe {
+            libc::kill(-pid, libc::SIGKILL);
+        }
+    }
+    #[cfg(not(unix))]
+    log::debug!(
+        "only killing process {}, anything it started keeps running",
+        child.id()
+    );
+    let _ = child.kill();
 }
 
-pub fn call_with_env(
-    command: impl IntoIterator<Item = impl Into<String>>,
-    envs: BTreeMap<&OsStr, &OsStr>,
-    path: &Path,
-    dry_run: bool,
-) -> CargoResult<bool> {
-    do_call(command, Some(path), Some(envs), dry_run)
+#[cfg(test)]
+#[cfg(unix)]
+mod test {
+    use super::*;
+
+    mod call_with_timeout {
+        use super::*;
+
+        fn sh(script: &str, timeout: Option<std::time::Duration>, path: &Path) -> Output {
+            call_with_timeout(
+                ["sh", "-c", script],
+                Default::default(),
+                Some(path),
+                timeout,
+                true,
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn captures_output() {
+            let temp = assert_fs::TempDir::new().unwrap();
+            let output = sh("echo out; echo err >&2; exit 3", None, temp.path());
+            assert!(!output.success());
+            assert!(!output.timed_out);
+            assert_eq!(output.status.unwrap().code(), Some(3));
+            assert!(output.output.contains("out"), "{}", output.output);
+            assert!(output.output.contains("err"), "{}", output.output);
+        }
+
+        #[test]
+        fn times_out() {
+            let temp = assert_fs::TempDir::new().unwrap();
+            let start = std::time::Instant::now();
+            let output = sh(
+                "echo started; sleep 10",
+                Some(std::time::Duration::from_millis(200)),
+                temp.path(),
+            );
+            assert!(output.timed_out);
+            assert!(!output.success());
+            assert!(output.output.contains("started"), "{}", output.output);
+            assert!(start.elapsed() < std::time::Duration::from_secs(5));
+        }
+
+        #[test]
+        fn kills_process_group() {
+            let temp = assert_fs::TempDir::new().unwrap();
+            let start = std::time::Instant::now();
+            // The background job holds the pipes open and would outlive the hook
+            let output = sh(
+                "(sleep 1; touch leaked) & sleep 10",
+                Some(std::time::Duration::from_millis(200)),
+                temp.path(),
+            );
+            assert!(output.timed_out);
+            assert!(start.elapsed() < std::time::Duration::from_secs(5));
+            std::thread::sleep(std::time::Duration::from_millis(1500));
+            assert!(!temp.path().join("leaked").exists());
+        }
+
+        #[test]
+        fn background_job_does_not_block() {
+            let temp = assert_fs::TempDir::new().unwrap();
+            let start = std::time::Instant::now();
+            let output = sh("sleep 5 & echo done", None, temp.path());
+            assert!(output.success());
+            assert!(output.output.contains("done"), "{}", output.output);
+            assert!(start.elapsed() < std::time::Duration::from_secs(3));
+        }
+    }
 }
@@ -169,6 +169,10 @@ pub fn tag(dir: &Path, name: &str, msg: &str, sign: bool, dry_run: bool) -> Carg
     call_on_path(cmd, dir, dry_run)
 }
 
+pub fn delete_tag(dir: &Path, name: &str, dry_run: bool) -> CargoResult<bool> {
+    call_on_path(vec!["git", "tag", "--delete", name], dir, dry_run)
+}
+
 pub fn tag_exists(dir: &Path, name: &str) -> CargoResult<bool> {
     let repo = git2::Repository::discover(dir)?;
 
@@ -237,6 +241,49 @@ pub fn push<'s>(
     call_on_path(command, dir, dry_run)
 }
 
+pub fn head_id(dir: &Path) -> CargoResult<String> {
+    let repo = git2::Repository::discover(dir)?;
+
+    let head_id = repo.head()?.peel_to_commit()?.id();
+    Ok(head_id.to_string())
+}
+
+/// Discard commits and local changes since `rev`
+pub fn reset_hard(dir: &Path, rev: &str, dry_run: bool) -> CargoResult<bool> {
+    call_on_path(vec!["git", "reset", "--hard", rev], dir, dry_run)
+}
+
+/// Check out `rev` into `path`, leaving the current working tree alone
+pub fn worktree_add(dir: &Path, path: &Path, rev: &str) -> CargoResult<bool> {
+    call_on_path(
+        vec![
+            "git",
+            "worktree",
+            "add",
+            "--quiet",
+            "--detach",
+            path.to_str().unwrap(),
+            rev,
+        ],
+        dir,
+        false,
+    )
+}
+
+pub fn worktree_remove(dir: &Path, path: &Path) -> CargoResult<bool> {
+    call_on_path(
+        vec![
+            "git",
+            "worktree",
+            "remove",
+            "--force",
+            path.to_str().unwrap(),
+        ],
+        dir,
+        false,
+    )
+}
+
 pub fn top_level(dir: &Path) -> CargoResult<PathBuf> {
     let repo = git2::Repository::discover(dir)?;
 
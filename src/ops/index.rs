@@ -1,23 +1,28 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
 use crate::config::CertsSource;
+use crate::error::CargoResult;
 use tame_index::krate::IndexKrate;
 use tame_index::utils::flock::FileLock;
 
+/// Index for crates.io or, when a `registry` is given, an alternate registry
 #[derive(Default)]
 pub struct CratesIoIndex {
-    index: Option<RemoteIndex>,
-    cache: std::collections::HashMap<String, Option<IndexKrate>>,
+    indexes: HashMap<Option<String>, RemoteIndex>,
+    cache: HashMap<(Option<String>, String), Option<IndexKrate>>,
 }
 
 impl CratesIoIndex {
     #[inline]
     pub fn new() -> Self {
         Self {
-            index: None,
-            cache: std::collections::HashMap::new(),
+            indexes: HashMap::new(),
+            cache: HashMap::new(),
         }
     }
 
-    /// Determines if the specified crate exists in the crates.io index
+    /// Determines if the specified crate exists in the registry's index
     #[inline]
     pub fn has_krate(
         &mut self,
@@ -31,7 +36,7 @@ impl CratesIoIndex {
             .unwrap_or(false))
     }
 
-    /// Determines if the specified crate version exists in the crates.io index
+    /// Determines if the specified crate version exists in the registry's index
     #[inline]
     pub fn has_krate_version(
         &mut self,
@@ -46,11 +51,18 @@ impl CratesIoIndex {
 
     #[inline]
     pub fn update_krate(&mut self, registry: Option<&str>, name: &str) {
-        if registry.is_some() {
-            return;
+        let registry = normalize_registry(registry).map(ToOwned::to_owned);
+        if let Some(index) = self.indexes.get_mut(&registry) {
+            index.invalidate();
         }
+        self.cache.remove(&(registry, name.to_owned()));
+    }
 
-        self.cache.remove(name);
+    /// Use `index` for `registry` instead of looking up where it is configured
+    #[cfg(test)]
+    pub(crate) fn insert(&mut self, registry: Option<&str>, index: RemoteIndex) {
+        let registry = normalize_registry(registry).map(ToOwned::to_owned);
+        self.indexes.insert(registry, index);
     }
 
     pub(crate) fn krate(
@@ -59,41 +71,123 @@ impl CratesIoIndex {
         name: &str,
         certs_source: CertsSource,
     ) -> Result<Option<IndexKrate>, crate::error::CliError> {
-        if let Some(registry) = registry {
-            log::trace!("Cannot connect to registry `{registry}`");
-            return Ok(None);
-        }
-
-        if let Some(entry) = self.cache.get(name) {
+        let registry = normalize_registry(registry);
+        let key = (registry.map(ToOwned::to_owned), name.to_owned());
+        if let Some(entry) = self.cache.get(&key) {
             log::trace!("Reusing index for {name}");
             return Ok(entry.clone());
         }
 
-        if self.index.is_none() {
-            log::trace!("Connecting to index");
-            self.index = Some(RemoteIndex::open(certs_source)?);
-        }
-        let index = self.index.as_mut().unwrap();
+        let index = match self.indexes.entry(key.0.clone()) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                log::trace!(
+                    "Connecting to index for {}",
+                    registry.unwrap_or("crates.io")
+                );
+                entry.insert(RemoteIndex::open(registry, certs_source)?)
+            }
+        };
         log::trace!("Downloading index for {name}");
         let entry = index.krate(name)?;
-        self.cache.insert(name.to_owned(), entry.clone());
+        self.cache.insert(key, entry.clone());
         Ok(entry)
     }
 }
 
-pub struct RemoteIndex {
+fn normalize_registry(registry: Option<&str>) -> Option<&str> {
+    registry.filter(|r| *r != "crates-io")
+}
+
+pub enum RemoteIndex {
+    /// Sparse index served over HTTP
+    Sparse(SparseRemoteIndex),
+    /// Sparse index or local registry read from a directory
+    Local(LocalIndex),
+    /// Git index, fetched with the `git` CLI
+    Git(GitRemoteIndex),
+}
+
+impl RemoteIndex {
+    /// Open the index for `registry`, as configured in `registries.<name>.index`
+    pub fn open(
+        registry: Option<&str>,
+        certs_source: CertsSource,
+    ) -> Result<Self, crate::error::CliError> {
+        let Some(registry) = registry else {
+            return Ok(Self::Sparse(SparseRemoteIndex::open(
+                None,
+                tame_index::IndexUrl::CratesIoSparse,
+                certs_source,
+            )?));
+        };
+
+        let url = tame_index::IndexUrl::for_registry_name(None, None, registry)?;
+        match url {
+            tame_index::IndexUrl::Local(path) => Ok(Self::Local(LocalIndex::new(
+                path.as_std_path().join("index"),
+            ))),
+            url => Self::from_url(Some(registry), url.as_str(), certs_source),
+        }
+    }
+
+    pub fn from_url(
+        registry: Option<&str>,
+        url: &str,
+        certs_source: CertsSource,
+    ) -> Result<Self, crate::error::CliError> {
+        if let Some(sparse_url) = url.strip_prefix("sparse+") {
+            if let Some(path) = sparse_url.strip_prefix("file://") {
+                Ok(Self::Local(LocalIndex::new(PathBuf::from(path))))
+            } else {
+                Ok(Self::Sparse(SparseRemoteIndex::open(
+                    registry,
+                    tame_index::IndexUrl::NonCratesIo(url.into()),
+                    certs_source,
+                )?))
+            }
+        } else {
+            Ok(Self::Git(GitRemoteIndex::new(url)?))
+        }
+    }
+
+    pub(crate) fn krate(
+        &mut self,
+        name: &str,
+    ) -> Result<Option<IndexKrate>, crate::error::CliError> {
+        match self {
+            Self::Sparse(index) => index.krate(name),
+            Self::Local(index) => index.krate(name),
+            Self::Git(index) => index.krate(name),
+        }
+    }
+
+    /// Ensure the next lookup sees the latest state of the index
+    fn invalidate(&mut self) {
+        if let Self::Git(index) = self {
+            index.fetched = false;
+        }
+    }
+}
+
+pub struct SparseRemoteIndex {
+    registry: Option<String>,
     index: tame_index::SparseIndex,
     client: tame_index::external::reqwest::blocking::Client,
     lock: FileLock,
     etags: Vec<(String, String)>,
+    token: Option<String>,
+    find_token: fn(&str) -> CargoResult<Option<String>>,
 }
 
-impl RemoteIndex {
+impl SparseRemoteIndex {
     #[inline]
-    pub fn open(certs_source: CertsSource) -> Result<Self, crate::error::CliError> {
-        let index = tame_index::SparseIndex::new(tame_index::IndexLocation::new(
-            tame_index::IndexUrl::CratesIoSparse,
-        ))?;
+    pub fn open(
+        registry: Option<&str>,
+        url: tame_index::IndexUrl<'_>,
+        certs_source: CertsSource,
+    ) -> Result<Self, crate::error::CliError> {
+        let index = tame_index::SparseIndex::new(tame_index::IndexLocation::new(url))?;
 
         let client = {
             let builder = tame_index::external::reqwest::blocking::ClientBuilder::new();
@@ -109,10 +203,13 @@ impl RemoteIndex {
         let lock = FileLock::unlocked();
 
         Ok(Self {
+            registry: registry.map(ToOwned::to_owned),
             index,
             client,
             lock,
             etags: Vec::new(),
+            token: None,
+            find_token: registry_token,
         })
     }
 
@@ -140,10 +237,30 @@ impl RemoteIndex {
             },
             _,
         ) = req.into_parts();
-        let mut req = self.client.request(method, uri.to_string());
-        req = req.version(version);
-        req = req.headers(headers);
-        let res = self.client.execute(req.build()?)?;
+        let res = loop {
+            let mut req = self.client.request(method.clone(), uri.to_string());
+            req = req.version(version);
+            req = req.headers(headers.clone());
+            if let Some(token) = self.token.as_deref() {
+                req = req.header(tame_index::external::reqwest::header::AUTHORIZATION, token);
+            }
+            let res = self.client.execute(req.build()?)?;
+
+            // Like cargo, only authenticate once the registry asks for it
+            if res.status() == tame_index::external::reqwest::StatusCode::UNAUTHORIZED
+                && self.token.is_none()
+                && let Some(registry) = self.registry.as_deref()
+            {
+                let token = (self.find_token)(registry)?.ok_or_else(|| {
+                    anyhow::format_err!(
+                        "registry `{registry}` requires authentication, run `cargo login --registry {registry}`"
+                    )
+                })?;
+                self.token = Some(token);
+                continue;
+            }
+            break res;
+        };
 
         // Grab the etag if it exists for future requests
         if let Some(etag) = res
@@ -177,3 +294,346 @@ impl RemoteIndex {
             .map_err(Into::into)
     }
 }
+
+/// Index laid out on disk like a sparse index, e.g. `sparse+file://` or a local registry
+pub struct LocalIndex {
+    root: PathBuf,
+}
+
+impl LocalIndex {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    pub(crate) fn krate(&self, name: &str) -> Result<Option<IndexKrate>, crate::error::CliError> {
+        let krate_name: tame_index::KrateName<'_> = name.try_into()?;
+        let path = self.root.join(krate_name.relative_path(None));
+        let data = match std::fs::read(&path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(anyhow::format_err!("failed to read {}: {err}", path.display()).into());
+            }
+        };
+        Ok(Some(IndexKrate::from_slice(&data)?))
+    }
+}
+
+pub struct GitRemoteIndex {
+    url: String,
+    path: PathBuf,
+    fetched: bool,
+}
+
+impl GitRemoteIndex {
+    pub fn new(url: &str) -> CargoResult<Self> {
+        let (cargo_path, _) =
+            tame_index::IndexLocation::new(tame_index::IndexUrl::NonCratesIo(url.into()))
+                .into_parts()?;
+        let dir_name = cargo_path.file_name().unwrap_or("index").to_owned();
+        // Keep our own checkout rather than racing cargo for its copy
+        let path = dirs_next::cache_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("cargo-release")
+            .join("index")
+            .join(dir_name);
+        Ok(Self {
+            url: url.to_owned(),
+            path,
+            fetched: false,
+        })
+    }
+
+    fn fetch(&mut self) -> CargoResult<()> {
+        if !self.path.join("HEAD").exists() {
+            std::fs::create_dir_all(&self.path)?;
+            git_in(&self.path, &["init", "--quiet", "--bare"])?;
+        }
+        log::trace!("fetching {}", self.url);
+        git_in(
+            &self.path,
+            &[
+                "fetch",
+                "--quiet",
+                "--depth=1",
+                "--force",
+                &self.url,
+                "+HEAD:refs/remotes/origin/HEAD",
+            ],
+        )?;
+        self.fetched = true;
+        Ok(())
+    }
+
+    pub(crate) fn krate(
+        &mut self,
+        name: &str,
+    ) -> Result<Option<IndexKrate>, crate::error::CliError> {
+        if !self.fetched {
+            self.fetch()?;
+        }
+
+        let krate_name: tame_index::KrateName<'_> = name.try_into()?;
+        let rel_path = krate_name.relative_path(Some('/'));
+        let read = || -> Result<Option<Vec<u8>>, git2::Error> {
+            let repo = git2::Repository::open_bare(&self.path)?;
+            let tree = repo
+                .find_reference("refs/remotes/origin/HEAD")?
+                .peel_to_tree()?;
+            let entry = match tree.get_path(Path::new(&rel_path)) {
+                Ok(entry) => entry,
+                Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+                Err(err) => return Err(err),
+            };
+            let blob = entry.to_object(&repo)?.peel_to_blob()?;
+            Ok(Some(blob.content().to_owned()))
+        };
+        let data = read().map_err(|e| {
+            anyhow::format_err!("failed to read {} from index {}: {e}", name, self.url)
+        })?;
+        data.map(|data| IndexKrate::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+}
+
+fn git_in(dir: &Path, args: &[&str]) -> CargoResult<()> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|_| anyhow::format_err!("`git` not found"))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Token for `registry` from `CARGO_REGISTRIES_<NAME>_TOKEN` or cargo's credentials file
+fn registry_token(registry: &str) -> CargoResult<Option<String>> {
+    let cargo_home = tame_index::utils::cargo_home()?;
+    find_token(
+        registry,
+        |key| std::env::var(key).ok(),
+        cargo_home.as_std_path(),
+    )
+}
+
+fn find_token(
+    registry: &str,
+    env: impl Fn(&str) -> Option<String>,
+    cargo_home: &Path,
+) -> CargoResult<Option<String>> {
+    let key = format!(
+        "CARGO_REGISTRIES_{}_TOKEN",
+        registry.to_uppercase().replace('-', "_")
+    );
+    if let Some(token) = env(&key) {
+        return Ok(Some(token));
+    }
+
+    for name in ["credentials.toml", "credentials"] {
+        let path = cargo_home.join(name);
+        let Ok(data) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let credentials: toml::Table = toml::from_str(&data)
+            .map_err(|e| anyhow::format_err!("failed to parse {}: {e}", path.display()))?;
+        let token = credentials
+            .get("registries")
+            .and_then(|r| r.get(registry))
+            .and_then(|r| r.get("token"))
+            .and_then(|t| t.as_str());
+        if let Some(token) = token {
+            return Ok(Some(token.to_owned()));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const FOO: &str = r#"{"name":"foo","vers":"0.1.0","deps":[],"cksum":"0000000000000000000000000000000000000000000000000000000000000000","features":{},"yanked":false}
+"#;
+
+    /// Lay out an index containing `foo` under `root`
+    fn write_index(root: &Path) {
+        let entry = root.join("3").join("f");
+        std::fs::create_dir_all(&entry).unwrap();
+        std::fs::write(entry.join("foo"), FOO).unwrap();
+    }
+
+    #[test]
+    fn local_sparse_index() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        write_index(temp.path());
+        let url = format!("sparse+file://{}/", temp.path().display());
+
+        let mut index = RemoteIndex::from_url(Some("local"), &url, CertsSource::Webpki).unwrap();
+        let krate = index.krate("foo").unwrap().unwrap();
+        assert_eq!(krate.versions.len(), 1);
+        assert_eq!(krate.versions[0].version, "0.1.0");
+        assert!(index.krate("bar").unwrap().is_none());
+    }
+
+    mod sparse_index {
+        use super::*;
+
+        use std::io::{BufRead as _, Write as _};
+
+        /// Serve `root` over HTTP as a sparse index, requiring `token` if set
+        fn serve(root: PathBuf, token: Option<&'static str>) -> String {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            let addr = listener.local_addr().unwrap();
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let Ok(mut stream) = stream else {
+                        continue;
+                    };
+                    let mut reader = std::io::BufReader::new(stream.try_clone().unwrap());
+                    let mut request = String::new();
+                    reader.read_line(&mut request).unwrap();
+                    let mut authorization = None;
+                    loop {
+                        let mut header = String::new();
+                        reader.read_line(&mut header).unwrap();
+                        let header = header.trim_end();
+                        if header.is_empty() {
+                            break;
+                        }
+                        if let Some((name, value)) = header.split_once(':')
+                            && name.eq_ignore_ascii_case("authorization")
+                        {
+                            authorization = Some(value.trim().to_owned());
+                        }
+                    }
+
+                    let path = request.split(' ').nth(1).unwrap_or("/");
+                    let (status, body) = if token.is_some() && authorization.as_deref() != token {
+                        ("401 Unauthorized", Vec::new())
+                    } else {
+                        match std::fs::read(root.join(path.trim_start_matches('/'))) {
+                            Ok(body) => ("200 OK", body),
+                            Err(_) => ("404 Not Found", Vec::new()),
+                        }
+                    };
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        body.len()
+                    );
+                    let _ = stream.write_all(&body);
+                }
+            });
+            format!("sparse+http://{addr}/")
+        }
+
+        fn open(url: &str, registry: &str) -> SparseRemoteIndex {
+            SparseRemoteIndex::open(
+                Some(registry),
+                tame_index::IndexUrl::NonCratesIo(url.into()),
+                CertsSource::Webpki,
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn found() {
+            let temp = assert_fs::TempDir::new().unwrap();
+            write_index(temp.path());
+            let url = serve(temp.path().to_owned(), None);
+
+            let mut index = open(&url, "local");
+            let krate = index.krate("foo").unwrap().unwrap();
+            assert_eq!(krate.versions.len(), 1);
+            assert_eq!(krate.versions[0].version, "0.1.0");
+        }
+
+        #[test]
+        fn missing_crate() {
+            let temp = assert_fs::TempDir::new().unwrap();
+            write_index(temp.path());
+            let url = serve(temp.path().to_owned(), None);
+
+            let mut index = open(&url, "local");
+            assert!(index.krate("bar").unwrap().is_none());
+        }
+
+        #[test]
+        fn authenticates_when_asked() {
+            let temp = assert_fs::TempDir::new().unwrap();
+            write_index(temp.path());
+            let url = serve(temp.path().to_owned(), Some("secret"));
+
+            let mut index = open(&url, "private");
+            index.find_token = |registry| {
+                assert_eq!(registry, "private");
+                Ok(Some("secret".to_owned()))
+            };
+            let krate = index.krate("foo").unwrap().unwrap();
+            assert_eq!(krate.versions[0].version, "0.1.0");
+            assert_eq!(index.token.as_deref(), Some("secret"));
+        }
+
+        #[test]
+        fn missing_token() {
+            let temp = assert_fs::TempDir::new().unwrap();
+            write_index(temp.path());
+            let url = serve(temp.path().to_owned(), Some("secret"));
+
+            let mut index = open(&url, "private");
+            index.find_token = |_| Ok(None);
+            let err = index.krate("foo").unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "registry `private` requires authentication, run `cargo login --registry private`"
+            );
+        }
+    }
+
+    mod find_token {
+        use super::*;
+
+        fn cargo_home() -> assert_fs::TempDir {
+            let temp = assert_fs::TempDir::new().unwrap();
+            std::fs::write(
+                temp.path().join("credentials.toml"),
+                "[registries.my-registry]\ntoken = \"from-file\"\n",
+            )
+            .unwrap();
+            temp
+        }
+
+        #[test]
+        fn env_first() {
+            let cargo_home = cargo_home();
+            let token = find_token(
+                "my-registry",
+                |key| (key == "CARGO_REGISTRIES_MY_REGISTRY_TOKEN").then(|| "from-env".to_owned()),
+                cargo_home.path(),
+            )
+            .unwrap();
+            assert_eq!(token.as_deref(), Some("from-env"));
+        }
+
+        #[test]
+        fn credentials_file() {
+            let cargo_home = cargo_home();
+            let token = find_token("my-registry", |_| None, cargo_home.path()).unwrap();
+            assert_eq!(token.as_deref(), Some("from-file"));
+        }
+
+        #[test]
+        fn unknown_registry() {
+            let cargo_home = cargo_home();
+            let token = find_token("other", |_| None, cargo_home.path()).unwrap();
+            assert_eq!(token, None);
+        }
+    }
+}
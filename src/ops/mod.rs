@@ -1,7 +1,10 @@
+pub mod api;
 pub mod cargo;
+pub mod changelog;
 pub mod cmd;
 pub mod git;
 pub mod index;
+pub mod rate_limit;
 pub mod replace;
 pub mod shell;
 pub mod version;
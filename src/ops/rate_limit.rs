@@ -0,0 +1,122 @@
+use std::time::{Duration, Instant};
+
+/// Token bucket for a registry rate limit, tracked as a "generic cell rate"
+///
+/// A full bucket allows `burst` requests at once, after which requests are spaced out by
+/// `refill`.
+#[derive(Clone, Debug)]
+pub struct Bucket {
+    tolerance: Duration,
+    refill: Duration,
+    theoretical_arrival: Instant,
+}
+
+impl Bucket {
+    pub fn new(burst: usize, refill: Duration, now: Instant) -> Self {
+        let burst = u32::try_from(burst.max(1)).unwrap_or(u32::MAX);
+        Self {
+            tolerance: refill * (burst - 1),
+            refill,
+            theoretical_arrival: now,
+        }
+    }
+
+    /// Reserve the next request, returning how long to wait before making it
+    pub fn reserve(&mut self, now: Instant) -> Duration {
+        let arrival = self.theoretical_arrival.max(now);
+        let wait = arrival
+            .checked_sub(self.tolerance)
+            .map(|allowed| allowed.saturating_duration_since(now))
+            .unwrap_or_default();
+        self.theoretical_arrival = arrival + self.refill;
+        wait
+    }
+
+    /// Hold off the next request for at least `wait`, e.g. after the registry told us to slow down
+    pub fn pause(&mut self, now: Instant, wait: Duration) {
+        self.theoretical_arrival = self.theoretical_arrival.max(now + wait + self.tolerance);
+    }
+
+    pub fn refill(&self) -> Duration {
+        self.refill
+    }
+}
+
+/// Find how long the registry asked us to wait in a `429 Too Many Requests` response
+///
+/// Returns `None` if `output` isn't a rate limit error and `Some(None)` if it is but no time to
+/// retry was given.
+pub fn retry_after(output: &str, now: time::OffsetDateTime) -> Option<Option<Duration>> {
+    if !output.contains("status 429") && !output.contains("Too Many Requests") {
+        return None;
+    }
+
+    const NEEDLE: &str = "try again after ";
+    let wait = output.find(NEEDLE).and_then(|start| {
+        let rest = &output[start + NEEDLE.len()..];
+        // `Fri, 18 Oct 2026 12:34:56 GMT`
+        let end = rest.find("GMT").map(|end| end + "GMT".len())?;
+        let retry_at = time::OffsetDateTime::parse(
+            &rest[..end],
+            &time::format_description::well_known::Rfc2822,
+        )
+        .ok()?;
+        let wait = retry_at - now;
+        Some(Duration::try_from(wait).unwrap_or_default())
+    });
+    Some(wait)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bucket_allows_burst_then_refills() {
+        let start = Instant::now();
+        let refill = Duration::from_secs(60);
+        let mut bucket = Bucket::new(3, refill, start);
+
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), refill);
+        assert_eq!(bucket.reserve(start), refill * 2);
+
+        // Idle time refills the bucket
+        let later = start + refill * 10;
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+    }
+
+    #[test]
+    fn paused_bucket_waits() {
+        let start = Instant::now();
+        let refill = Duration::from_secs(600);
+        let mut bucket = Bucket::new(5, refill, start);
+
+        bucket.pause(start, Duration::from_secs(42));
+        assert_eq!(bucket.reserve(start), Duration::from_secs(42));
+        assert_eq!(bucket.reserve(start), Duration::from_secs(42) + refill);
+    }
+
+    #[test]
+    fn retry_after_crates_io() {
+        let now = time::macros::datetime!(2026-10-18 12:00:00 UTC);
+        let output = "error: failed to publish to registry at https://crates.io
+
+Caused by:
+  the remote server responded with an error (status 429 Too Many Requests): You have published too many new crates in a short period of time. Please try again after Sun, 18 Oct 2026 12:10:00 GMT or email help@crates.io to have your limit increased.
+";
+        assert_eq!(
+            retry_after(output, now),
+            Some(Some(Duration::from_secs(600)))
+        );
+    }
+
+    #[test]
+    fn retry_after_without_time() {
+        let now = time::macros::datetime!(2026-10-18 12:00:00 UTC);
+        assert_eq!(retry_after("status 429 Too Many Requests", now), Some(None));
+        assert_eq!(retry_after("error: 401 Unauthorized", now), None);
+    }
+}
@@ -1,11 +1,14 @@
 use std::collections::BTreeMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::config::Replace;
+use crate::config::{Replace, ReplaceCount};
 use crate::error::CargoResult;
 
+pub static TODAY: std::sync::LazyLock<time::Date> =
+    std::sync::LazyLock::new(|| time::OffsetDateTime::now_utc().date());
+
 pub static NOW: std::sync::LazyLock<String> = std::sync::LazyLock::new(|| {
-    time::OffsetDateTime::now_utc()
+    TODAY
         .format(time::macros::format_description!("[year]-[month]-[day]"))
         .unwrap()
 });
@@ -71,11 +74,18 @@ pub fn do_file_replacements(
 ) -> CargoResult<bool> {
     // Since we don't have a convenient insert-order map, let's do sorted, rather than random.
     let mut by_file = BTreeMap::new();
-    for replace in replace_config {
-        let file = replace.file.clone();
-        by_file.entry(file).or_insert_with(Vec::new).push(replace);
+    for (index, replace) in replace_config.iter().enumerate() {
+        for file in replace_files(replace, cwd)? {
+            by_file
+                .entry(file)
+                .or_insert_with(Vec::new)
+                .push((index, replace));
+        }
     }
 
+    // Check every count before changing any file
+    let mut totals = vec![Some(0); replace_config.len()];
+    let mut changes = Vec::new();
     for (path, replaces) in by_file {
         let file = cwd.join(&path);
         log::debug!("processing replacements for file {}", file.display());
@@ -85,34 +95,26 @@ pub fn do_file_replacements(
         let data = std::fs::read_to_string(&file)?;
         let mut replaced = data.clone();
 
-        for replace in replaces {
+        for (index, replace) in replaces {
             if prerelease && !replace.prerelease {
                 log::debug!("pre-release, not replacing {}", replace.search);
+                totals[index] = None;
                 continue;
             }
 
             let pattern = replace.search.as_str();
             let r = regex::RegexBuilder::new(pattern).multi_line(true).build()?;
 
-            let min = replace.min.or(replace.exactly).unwrap_or(1);
-            let max = replace.max.or(replace.exactly).unwrap_or(usize::MAX);
             let actual = r.find_iter(&replaced).count();
-            if actual < min {
-                anyhow::bail!(
-                    "for `{}` in '{}', at least {} replacements expected, found {}",
-                    pattern,
-                    path.display(),
-                    min,
-                    actual
-                );
-            } else if max < actual {
-                anyhow::bail!(
-                    "for `{}` in '{}', at most {} replacements expected, found {}",
-                    pattern,
-                    path.display(),
-                    max,
-                    actual
-                );
+            match replace.count {
+                ReplaceCount::PerFile => {
+                    check_count(replace, actual, &format!("'{}'", path.display()))?;
+                }
+                ReplaceCount::Total => {
+                    if let Some(total) = totals[index].as_mut() {
+                        *total += actual;
+                    }
+                }
             }
 
             let to_replace = replace.replace.as_str();
@@ -121,6 +123,16 @@ pub fn do_file_replacements(
             replaced = r.replace_all(&replaced, replacer.as_str()).into_owned();
         }
 
+        changes.push((path, file, data, replaced));
+    }
+    for (replace, total) in replace_config.iter().zip(totals) {
+        if let (ReplaceCount::Total, Some(total)) = (replace.count, total) {
+            let files = format!("files matching '{}'", replace.file.display());
+            check_count(replace, total, &files)?;
+        }
+    }
+
+    for (path, file, data, replaced) in changes {
         if data != replaced {
             if dry_run {
                 if noisy {
@@ -145,3 +157,218 @@ pub fn do_file_replacements(
     }
     Ok(true)
 }
+
+/// Files, relative to `cwd`, that `replace` applies to
+fn replace_files(replace: &Replace, cwd: &Path) -> CargoResult<Vec<PathBuf>> {
+    let pattern = replace.file.to_string_lossy();
+    if !pattern.contains(['*', '?', '[', '{']) {
+        if !replace.exclude.is_empty() {
+            anyhow::bail!("`exclude` requires `file` to be a glob, found '{pattern}'");
+        }
+        return Ok(vec![replace.file.clone()]);
+    }
+
+    let glob = globset::GlobBuilder::new(&pattern)
+        .literal_separator(true)
+        .build()?
+        .compile_matcher();
+    let mut exclude = globset::GlobSetBuilder::new();
+    for pattern in &replace.exclude {
+        exclude.add(
+            globset::GlobBuilder::new(pattern)
+                .literal_separator(true)
+                .build()?,
+        );
+    }
+    let exclude = exclude.build()?;
+
+    let mut files = Vec::new();
+    // Dot-files can be released too, only git's own directory is never interesting
+    let walk = ignore::WalkBuilder::new(cwd)
+        .hidden(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build();
+    for entry in walk {
+        let entry = entry?;
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let Ok(path) = entry.path().strip_prefix(cwd) else {
+            continue;
+        };
+        if glob.is_match(path) && !exclude.is_match(path) {
+            files.push(path.to_owned());
+        }
+    }
+    files.sort();
+
+    let min = replace.min.or(replace.exactly).unwrap_or(1);
+    if files.is_empty() && 0 < min {
+        anyhow::bail!("no files match '{pattern}' to perform replace");
+    }
+    Ok(files)
+}
+
+fn check_count(replace: &Replace, actual: usize, files: &str) -> CargoResult<()> {
+    let pattern = replace.search.as_str();
+    let min = replace.min.or(replace.exactly).unwrap_or(1);
+    let max = replace.max.or(replace.exactly).unwrap_or(usize::MAX);
+    if actual < min {
+        anyhow::bail!(
+            "for `{pattern}` in {files}, at least {min} replacements expected, found {actual}"
+        );
+    } else if max < actual {
+        anyhow::bail!(
+            "for `{pattern}` in {files}, at most {max} replacements expected, found {actual}"
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[allow(unused_imports, reason = "prelude false positive")]
+    use assert_fs::prelude::*;
+    use predicates::prelude::*;
+
+    fn replace(file: &str, exclude: &[&str], count: ReplaceCount) -> Replace {
+        Replace {
+            file: PathBuf::from(file),
+            exclude: exclude.iter().map(|s| (*s).to_owned()).collect(),
+            search: "0\\.1\\.0".to_owned(),
+            replace: "{{version}}".to_owned(),
+            min: None,
+            max: None,
+            exactly: None,
+            count,
+            prerelease: false,
+        }
+    }
+
+    fn template() -> Template<'static> {
+        Template {
+            version: Some("0.2.0"),
+            ..Default::default()
+        }
+    }
+
+    mod do_file_replacements {
+        use super::*;
+
+        #[test]
+        fn glob_with_exclude() {
+            let temp = assert_fs::TempDir::new().unwrap();
+            temp.child("docs/a.md").write_str("v0.1.0").unwrap();
+            temp.child("docs/guide/b.md").write_str("v0.1.0").unwrap();
+            temp.child("docs/old.md").write_str("v0.1.0").unwrap();
+            temp.child("README.md").write_str("v0.1.0").unwrap();
+
+            let config = [replace(
+                "docs/**/*.md",
+                &["docs/old.md"],
+                ReplaceCount::PerFile,
+            )];
+            do_file_replacements(&config, &template(), temp.path(), false, false, false).unwrap();
+
+            temp.child("docs/a.md").assert("v0.2.0");
+            temp.child("docs/guide/b.md").assert("v0.2.0");
+            temp.child("docs/old.md").assert("v0.1.0");
+            temp.child("README.md").assert("v0.1.0");
+
+            temp.close().unwrap();
+        }
+
+        #[test]
+        fn glob_includes_hidden() {
+            let temp = assert_fs::TempDir::new().unwrap();
+            temp.child(".github/workflows/ci.yml")
+                .write_str("v0.1.0")
+                .unwrap();
+            temp.child(".git/config").write_str("v0.1.0").unwrap();
+
+            let config = [replace("**/*", &[], ReplaceCount::PerFile)];
+            do_file_replacements(&config, &template(), temp.path(), false, false, false).unwrap();
+
+            temp.child(".github/workflows/ci.yml").assert("v0.2.0");
+            temp.child(".git/config").assert("v0.1.0");
+
+            temp.close().unwrap();
+        }
+
+        #[test]
+        fn exclude_requires_glob() {
+            let temp = assert_fs::TempDir::new().unwrap();
+            temp.child("README.md").write_str("v0.1.0").unwrap();
+
+            let config = [replace("README.md", &["README.md"], ReplaceCount::PerFile)];
+            let err = do_file_replacements(&config, &template(), temp.path(), false, false, false)
+                .unwrap_err();
+            assert!(err.to_string().contains("`exclude`"), "{err}");
+            temp.child("README.md").assert("v0.1.0");
+
+            temp.close().unwrap();
+        }
+
+        #[test]
+        fn per_file_count_checked_before_writing() {
+            let temp = assert_fs::TempDir::new().unwrap();
+            temp.child("examples/a/Cargo.toml")
+                .write_str("0.1.0")
+                .unwrap();
+            temp.child("examples/b/Cargo.toml").write_str("").unwrap();
+
+            let config = [replace("examples/*/Cargo.toml", &[], ReplaceCount::PerFile)];
+            let err = do_file_replacements(&config, &template(), temp.path(), false, false, false)
+                .unwrap_err();
+            assert!(err.to_string().contains("examples/b/Cargo.toml"), "{err}");
+
+            temp.child("examples/a/Cargo.toml")
+                .assert(predicate::str::diff("0.1.0"));
+
+            temp.close().unwrap();
+        }
+
+        #[test]
+        fn total_count() {
+            let temp = assert_fs::TempDir::new().unwrap();
+            temp.child("examples/a/Cargo.toml")
+                .write_str("0.1.0")
+                .unwrap();
+            temp.child("examples/b/Cargo.toml").write_str("").unwrap();
+
+            let mut config = [replace("examples/*/Cargo.toml", &[], ReplaceCount::Total)];
+            config[0].exactly = Some(1);
+            do_file_replacements(&config, &template(), temp.path(), false, false, false).unwrap();
+            temp.child("examples/a/Cargo.toml").assert("0.2.0");
+
+            config[0].exactly = Some(2);
+            let err = do_file_replacements(&config, &template(), temp.path(), false, false, false)
+                .unwrap_err();
+            assert!(
+                err.to_string()
+                    .contains("files matching 'examples/*/Cargo.toml'"),
+                "{err}"
+            );
+
+            temp.close().unwrap();
+        }
+
+        #[test]
+        fn no_matches() {
+            let temp = assert_fs::TempDir::new().unwrap();
+
+            let mut config = [replace("docs/*.md", &[], ReplaceCount::PerFile)];
+            assert!(
+                do_file_replacements(&config, &template(), temp.path(), false, false, false)
+                    .is_err()
+            );
+
+            config[0].min = Some(0);
+            do_file_replacements(&config, &template(), temp.path(), false, false, false).unwrap();
+
+            temp.close().unwrap();
+        }
+    }
+}
@@ -10,24 +10,16 @@ pub trait VersionExt {
     fn increment_minor(&mut self);
     /// Increments the patch version number for this Version.
     fn increment_patch(&mut self);
-    /// Increment the alpha pre-release number for this Version.
+    /// Increment the pre-release number for `channel`.
     ///
-    /// If this isn't alpha, switch to it.
+    /// If this isn't `channel`, switch to it.
     ///
-    /// Errors if this would decrement the pre-release phase.
-    fn increment_alpha(&mut self) -> CargoResult<()>;
-    /// Increment the beta pre-release number for this Version.
+    /// Errors if this would go back to an earlier entry in `channels`.
+    fn increment_prerelease(&mut self, channel: &str, channels: &[String]) -> CargoResult<()>;
+    /// Set the version from `today` per a calendar versioning `format`, like `YYYY.MM.MICRO`
     ///
-    /// If this isn't beta, switch to it.
-    ///
-    /// Errors if this would decrement the pre-release phase.
-    fn increment_beta(&mut self) -> CargoResult<()>;
-    /// Increment the rc pre-release number for this Version.
-    ///
-    /// If this isn't rc, switch to it.
-    ///
-    /// Errors if this would decrement the pre-release phase.
-    fn increment_rc(&mut self) -> CargoResult<()>;
+    /// `MICRO` is incremented when the date part is unchanged and reset otherwise.
+    fn increment_calver(&mut self, format: &str, today: time::Date) -> CargoResult<()>;
     /// Append informational-only metadata.
     fn metadata(&mut self, metadata: &str) -> CargoResult<()>;
     /// Checks to see if the current Version is in pre-release status
@@ -56,64 +48,92 @@ impl VersionExt for semver::Version {
         self.build = semver::BuildMetadata::EMPTY;
     }
 
-    fn increment_alpha(&mut self) -> CargoResult<()> {
+    fn increment_prerelease(&mut self, channel: &str, channels: &[String]) -> CargoResult<()> {
+        let Some(channel_rank) = channels.iter().position(|c| c == channel) else {
+            anyhow::bail!(
+                "`{channel}` is not a pre-release channel, expected one of {}",
+                channels.join(", ")
+            );
+        };
         if let Some((pre_ext, pre_ext_ver)) = prerelease_id_version(self)? {
-            if pre_ext == VERSION_BETA || pre_ext == VERSION_RC {
-                Err(anyhow::format_err!(
-                    "unsupported release level {VERSION_ALPHA}, only major, minor, and patch are supported"
-                ))
+            let new_ext_ver = if pre_ext == channel {
+                pre_ext_ver.unwrap_or(0) + 1
             } else {
-                let new_ext_ver = if pre_ext == VERSION_ALPHA {
-                    pre_ext_ver.unwrap_or(0) + 1
-                } else {
-                    1
-                };
-                self.pre = semver::Prerelease::new(&format!("{VERSION_ALPHA}.{new_ext_ver}"))?;
-                Ok(())
-            }
+                let pre_ext_rank = channels.iter().position(|c| *c == pre_ext);
+                if pre_ext_rank.map(|r| channel_rank < r).unwrap_or(false) {
+                    anyhow::bail!(
+                        "cannot go from pre-release `{pre_ext}` back to `{channel}`, pre-release channels are ordered {}",
+                        channels.join(", ")
+                    );
+                }
+                1
+            };
+            self.pre = semver::Prerelease::new(&format!("{channel}.{new_ext_ver}"))?;
+            Ok(())
         } else {
             self.increment_patch();
-            self.pre = semver::Prerelease::new(&format!("{VERSION_ALPHA}.1"))?;
+            self.pre = semver::Prerelease::new(&format!("{channel}.1"))?;
             Ok(())
         }
     }
 
-    fn increment_beta(&mut self) -> CargoResult<()> {
-        if let Some((pre_ext, pre_ext_ver)) = prerelease_id_version(self)? {
-            if pre_ext == VERSION_RC {
-                Err(anyhow::format_err!(
-                    "unsupported release level {VERSION_BETA}, only major, minor, and patch are supported"
-                ))
-            } else {
-                let new_ext_ver = if pre_ext == VERSION_BETA {
-                    pre_ext_ver.unwrap_or(0) + 1
-                } else {
-                    1
-                };
-                self.pre = semver::Prerelease::new(&format!("{VERSION_BETA}.{new_ext_ver}"))?;
-                Ok(())
-            }
+    fn increment_calver(&mut self, format: &str, today: time::Date) -> CargoResult<()> {
+        let components = format.split('.').collect::<Vec<_>>();
+        let [major, minor, patch] = components.as_slice() else {
+            anyhow::bail!(
+                "calver format `{format}` must have three components, like `YYYY.MM.MICRO`"
+            );
+        };
+        // Weeks belong to the ISO week-numbering year, which differs from the calendar year at its
+        // edges (2027-01-01 is in week 53 of 2026)
+        let year = if components.iter().any(|c| matches!(*c, "WW" | "0W")) {
+            today.to_iso_week_date().0
         } else {
-            self.increment_patch();
-            self.pre = semver::Prerelease::new(&format!("{VERSION_BETA}.1"))?;
-            Ok(())
+            today.year()
+        };
+        let current = [self.major, self.minor, self.patch];
+        let mut next = [0; 3];
+        let mut micro = None;
+        for (i, component) in [major, minor, patch].into_iter().enumerate() {
+            next[i] = match *component {
+                "YYYY" => u64::try_from(year)?,
+                // Short years are relative to 2000, per calver.org
+                "YY" | "0Y" => u64::try_from(year - 2000)?,
+                "MM" | "0M" => u64::from(u8::from(today.month())),
+                "WW" | "0W" => u64::from(today.iso_week()),
+                "DD" | "0D" => u64::from(today.day()),
+                "MICRO" if i == 2 => {
+                    micro = Some(i);
+                    continue;
+                }
+                "MICRO" => {
+                    anyhow::bail!("calver format `{format}` must end with `MICRO`, if present")
+                }
+                _ => anyhow::bail!(
+                    "unsupported calver component `{component}` in `{format}`, expected one of `YYYY`, `YY`, `0Y`, `MM`, `0M`, `WW`, `0W`, `DD`, `0D`, `MICRO`"
+                ),
+            };
         }
-    }
-
-    fn increment_rc(&mut self) -> CargoResult<()> {
-        if let Some((pre_ext, pre_ext_ver)) = prerelease_id_version(self)? {
-            let new_ext_ver = if pre_ext == VERSION_RC {
-                pre_ext_ver.unwrap_or(0) + 1
+        if let Some(micro) = micro {
+            let same_date = next[..micro] == current[..micro];
+            next[micro] = if !same_date {
+                0
+            } else if self.is_prerelease() {
+                // Releasing the pre-release
+                current[micro]
             } else {
-                1
+                current[micro] + 1
             };
-            self.pre = semver::Prerelease::new(&format!("{VERSION_RC}.{new_ext_ver}"))?;
-            Ok(())
-        } else {
-            self.increment_patch();
-            self.pre = semver::Prerelease::new(&format!("{VERSION_RC}.1"))?;
-            Ok(())
+        } else if next == current && !self.is_prerelease() {
+            anyhow::bail!(
+                "{self} already matches today's date, add `MICRO` to calver format `{format}` to release more than once"
+            );
         }
+
+        [self.major, self.minor, self.patch] = next;
+        self.pre = semver::Prerelease::EMPTY;
+        self.build = semver::BuildMetadata::EMPTY;
+        Ok(())
     }
 
     fn metadata(&mut self, build: &str) -> CargoResult<()> {
@@ -130,6 +150,13 @@ static VERSION_ALPHA: &str = "alpha";
 static VERSION_BETA: &str = "beta";
 static VERSION_RC: &str = "rc";
 
+/// Pre-release channels, from least to most stable, when none are configured
+pub fn default_channels() -> Vec<String> {
+    [VERSION_ALPHA, VERSION_BETA, VERSION_RC]
+        .map(ToOwned::to_owned)
+        .to_vec()
+}
+
 fn prerelease_id_version(version: &semver::Version) -> CargoResult<Option<(String, Option<u64>)>> {
     if !version.pre.is_empty() {
         if let Some((alpha, numeric)) = version.pre.as_str().split_once('.') {
@@ -154,12 +181,16 @@ pub fn upgrade_requirement(req: &str, version: &semver::Version) -> CargoResult<
         // Empty matches everything, no-change.
         Ok(None)
     } else {
-        let comparators: Result<Vec<_>, _> = raw_req
-            .comparators
-            .into_iter()
-            .map(|p| set_comparator(p, version))
-            .collect();
-        let comparators = comparators?;
+        let comparators = if raw_req.comparators.iter().any(is_range) {
+            set_range(raw_req.comparators, version)?
+        } else {
+            let comparators: Result<Vec<_>, _> = raw_req
+                .comparators
+                .into_iter()
+                .map(|p| set_comparator(p, version))
+                .collect();
+            comparators?
+        };
         let new_req = semver::VersionReq { comparators };
         let mut new_req_text = new_req.to_string();
         if new_req_text.starts_with('^') && !req.starts_with('^') {
@@ -197,9 +228,6 @@ fn set_comparator(
             Ok(pred)
         }
         semver::Op::Exact => Ok(assign_partial_req(version, pred)),
-        semver::Op::Greater | semver::Op::GreaterEq | semver::Op::Less | semver::Op::LessEq => Err(
-            anyhow::format_err!("support for modifying {pred} is currently unsupported"),
-        ),
         semver::Op::Tilde => Ok(assign_partial_req(version, pred)),
         semver::Op::Caret => Ok(assign_partial_req(version, pred)),
         _ => {
@@ -211,6 +239,109 @@ fn set_comparator(
     }
 }
 
+fn is_range(pred: &semver::Comparator) -> bool {
+    matches!(
+        pred.op,
+        semver::Op::Greater | semver::Op::GreaterEq | semver::Op::Less | semver::Op::LessEq
+    )
+}
+
+/// Widen a requirement with explicit bounds, like `>=0.3, <0.5`, to include `version`
+///
+/// Bounds that already include `version` are left alone, so compatible bumps keep the lower bound
+/// while incompatible bumps move the upper bound, at the precision the user wrote it with.
+fn set_range(
+    comparators: Vec<semver::Comparator>,
+    version: &semver::Version,
+) -> CargoResult<Vec<semver::Comparator>> {
+    // Pre-release matching is handled separately below
+    let release = semver::Version::new(version.major, version.minor, version.patch);
+    let mut comparators = comparators
+        .into_iter()
+        .map(|pred| {
+            let req = semver::VersionReq {
+                comparators: vec![pred.clone()],
+            };
+            if req.matches(&release) {
+                return Ok(pred);
+            }
+            match pred.op {
+                semver::Op::Less => Ok(raise_upper_bound(pred, &release)),
+                semver::Op::LessEq => Ok(assign_partial_req(&release, pred)),
+                semver::Op::Greater | semver::Op::GreaterEq => {
+                    let mut pred = assign_partial_req(&release, pred);
+                    pred.op = semver::Op::GreaterEq;
+                    Ok(pred)
+                }
+                _ => set_comparator(pred, version),
+            }
+        })
+        .collect::<CargoResult<Vec<_>>>()?;
+
+    // Pre-releases only match a requirement that names them, so raise the lower bound to it
+    if !version.pre.is_empty() {
+        let lower = semver::Comparator {
+            op: semver::Op::GreaterEq,
+            major: version.major,
+            minor: Some(version.minor),
+            patch: Some(version.patch),
+            pre: version.pre.clone(),
+        };
+        let named = comparators.iter().any(|pred| {
+            pred.major == lower.major
+                && pred.minor == lower.minor
+                && pred.patch == lower.patch
+                && pred.pre == lower.pre
+        });
+        if !named {
+            comparators
+                .retain(|pred| !matches!(pred.op, semver::Op::Greater | semver::Op::GreaterEq));
+            comparators.insert(0, lower);
+        }
+    }
+
+    Ok(comparators)
+}
+
+/// Move an exclusive upper bound just past `version`
+///
+/// The bound is raised at its least significant non-zero field, so `<0.5` and `<0.5.0` both
+/// become `<0.6` / `<0.6.0` when releasing `0.5.x`.
+fn raise_upper_bound(
+    mut pred: semver::Comparator,
+    version: &semver::Version,
+) -> semver::Comparator {
+    let minor_significant =
+        pred.minor.is_some_and(|m| m != 0) || pred.patch.is_some_and(|p| p != 0);
+    let patch_significant = pred.patch.is_some_and(|p| p != 0);
+
+    pred.major = version.major;
+    if pred.minor.is_some() {
+        pred.minor = Some(version.minor);
+    }
+    if pred.patch.is_some() {
+        pred.patch = Some(version.patch);
+    }
+    if patch_significant {
+        pred.patch = Some(version.patch + 1);
+    } else if minor_significant {
+        pred.minor = Some(version.minor + 1);
+        if pred.patch.is_some() {
+            pred.patch = Some(0);
+        }
+    } else {
+        pred.major = version.major + 1;
+        if pred.minor.is_some() {
+            pred.minor = Some(0);
+        }
+        if pred.patch.is_some() {
+            pred.patch = Some(0);
+        }
+    }
+    pred.pre = semver::Prerelease::EMPTY;
+    pred
+}
+
 fn assign_partial_req(
     version: &semver::Version,
     mut pred: semver::Comparator,
@@ -236,55 +367,71 @@ mod test {
         #[test]
         fn alpha() {
             let mut v = semver::Version::parse("1.0.0").unwrap();
-            v.increment_alpha().unwrap();
+            v.increment_prerelease(VERSION_ALPHA, &default_channels())
+                .unwrap();
             assert_eq!(v, semver::Version::parse("1.0.1-alpha.1").unwrap());
 
             let mut v2 = semver::Version::parse("1.0.1-dev").unwrap();
-            v2.increment_alpha().unwrap();
+            v2.increment_prerelease(VERSION_ALPHA, &default_channels())
+                .unwrap();
             assert_eq!(v2, semver::Version::parse("1.0.1-alpha.1").unwrap());
 
             let mut v3 = semver::Version::parse("1.0.1-alpha.1").unwrap();
-            v3.increment_alpha().unwrap();
+            v3.increment_prerelease(VERSION_ALPHA, &default_channels())
+                .unwrap();
             assert_eq!(v3, semver::Version::parse("1.0.1-alpha.2").unwrap());
 
             let mut v4 = semver::Version::parse("1.0.1-beta.1").unwrap();
-            assert!(v4.increment_alpha().is_err());
+            assert!(
+                v4.increment_prerelease(VERSION_ALPHA, &default_channels())
+                    .is_err()
+            );
         }
 
         #[test]
         fn beta() {
             let mut v = semver::Version::parse("1.0.0").unwrap();
-            v.increment_beta().unwrap();
+            v.increment_prerelease(VERSION_BETA, &default_channels())
+                .unwrap();
             assert_eq!(v, semver::Version::parse("1.0.1-beta.1").unwrap());
 
             let mut v2 = semver::Version::parse("1.0.1-dev").unwrap();
-            v2.increment_beta().unwrap();
+            v2.increment_prerelease(VERSION_BETA, &default_channels())
+                .unwrap();
             assert_eq!(v2, semver::Version::parse("1.0.1-beta.1").unwrap());
 
             let mut v2 = semver::Version::parse("1.0.1-alpha.1").unwrap();
-            v2.increment_beta().unwrap();
+            v2.increment_prerelease(VERSION_BETA, &default_channels())
+                .unwrap();
             assert_eq!(v2, semver::Version::parse("1.0.1-beta.1").unwrap());
 
             let mut v3 = semver::Version::parse("1.0.1-beta.1").unwrap();
-            v3.increment_beta().unwrap();
+            v3.increment_prerelease(VERSION_BETA, &default_channels())
+                .unwrap();
             assert_eq!(v3, semver::Version::parse("1.0.1-beta.2").unwrap());
 
             let mut v4 = semver::Version::parse("1.0.1-rc.1").unwrap();
-            assert!(v4.increment_beta().is_err());
+            assert!(
+                v4.increment_prerelease(VERSION_BETA, &default_channels())
+                    .is_err()
+            );
         }
 
         #[test]
         fn rc() {
             let mut v = semver::Version::parse("1.0.0").unwrap();
-            v.increment_rc().unwrap();
+            v.increment_prerelease(VERSION_RC, &default_channels())
+                .unwrap();
             assert_eq!(v, semver::Version::parse("1.0.1-rc.1").unwrap());
 
             let mut v2 = semver::Version::parse("1.0.1-dev").unwrap();
-            v2.increment_rc().unwrap();
+            v2.increment_prerelease(VERSION_RC, &default_channels())
+                .unwrap();
             assert_eq!(v2, semver::Version::parse("1.0.1-rc.1").unwrap());
 
             let mut v3 = semver::Version::parse("1.0.1-rc.1").unwrap();
-            v3.increment_rc().unwrap();
+            v3.increment_prerelease(VERSION_RC, &default_channels())
+                .unwrap();
             assert_eq!(v3, semver::Version::parse("1.0.1-rc.2").unwrap());
         }
 
@@ -296,6 +443,117 @@ mod test {
         }
     }
 
+    mod prerelease {
+        use super::*;
+
+        fn channels() -> Vec<String> {
+            ["dev", "nightly", "pre"].map(ToOwned::to_owned).to_vec()
+        }
+
+        #[test]
+        fn enter() {
+            let mut v = semver::Version::parse("1.0.0").unwrap();
+            v.increment_prerelease("nightly", &channels()).unwrap();
+            assert_eq!(v, semver::Version::parse("1.0.1-nightly.1").unwrap());
+        }
+
+        #[test]
+        fn increment() {
+            let mut v = semver::Version::parse("1.0.1-nightly.1").unwrap();
+            v.increment_prerelease("nightly", &channels()).unwrap();
+            assert_eq!(v, semver::Version::parse("1.0.1-nightly.2").unwrap());
+        }
+
+        #[test]
+        fn switch() {
+            let mut v = semver::Version::parse("1.0.1-dev.3").unwrap();
+            v.increment_prerelease("pre", &channels()).unwrap();
+            assert_eq!(v, semver::Version::parse("1.0.1-pre.1").unwrap());
+
+            // Unknown identifiers can move to any channel
+            let mut v = semver::Version::parse("1.0.1-alpha.3").unwrap();
+            v.increment_prerelease("dev", &channels()).unwrap();
+            assert_eq!(v, semver::Version::parse("1.0.1-dev.1").unwrap());
+        }
+
+        #[test]
+        fn downgrade() {
+            let mut v = semver::Version::parse("1.0.1-pre.1").unwrap();
+            assert!(v.increment_prerelease("dev", &channels()).is_err());
+        }
+
+        #[test]
+        fn unknown_channel() {
+            let mut v = semver::Version::parse("1.0.0").unwrap();
+            assert!(v.increment_prerelease("alpha", &channels()).is_err());
+        }
+    }
+
+    mod calver {
+        use super::*;
+
+        #[track_caller]
+        fn assert_calver(current: &str, format: &str, expected: &str) {
+            let today = time::macros::date!(2024 - 03 - 07);
+            let mut v = semver::Version::parse(current).unwrap();
+            v.increment_calver(format, today).unwrap();
+            assert_eq!(v, semver::Version::parse(expected).unwrap());
+        }
+
+        #[test]
+        fn new_date_resets_micro() {
+            assert_calver("2024.2.5", "YYYY.MM.MICRO", "2024.3.0");
+            assert_calver("24.2.5", "YY.0M.MICRO", "24.3.0");
+            assert_calver("1.2.3", "YYYY.0W.MICRO", "2024.10.0");
+        }
+
+        #[test]
+        fn same_date_increments_micro() {
+            assert_calver("2024.3.0", "YYYY.MM.MICRO", "2024.3.1");
+            assert_calver("2024.3.1+foo", "YYYY.MM.MICRO", "2024.3.2");
+            assert_calver("2024.3.1-rc.1", "YYYY.MM.MICRO", "2024.3.1");
+        }
+
+        #[test]
+        fn date_only() {
+            assert_calver("2024.3.6", "YYYY.MM.DD", "2024.3.7");
+
+            let mut v = semver::Version::parse("2024.3.7").unwrap();
+            assert!(
+                v.increment_calver("YYYY.MM.DD", time::macros::date!(2024 - 03 - 07))
+                    .is_err()
+            );
+        }
+
+        #[test]
+        fn week_crosses_year() {
+            let mut v = semver::Version::parse("2026.52.0").unwrap();
+            v.increment_calver("YYYY.0W.MICRO", time::macros::date!(2027 - 01 - 01))
+                .unwrap();
+            assert_eq!(v, semver::Version::parse("2026.53.0").unwrap());
+
+            let mut v = semver::Version::parse("24.52.0").unwrap();
+            v.increment_calver("YY.WW.MICRO", time::macros::date!(2024 - 12 - 30))
+                .unwrap();
+            assert_eq!(v, semver::Version::parse("25.1.0").unwrap());
+
+            // Without weeks, the calendar year is used
+            let mut v = semver::Version::parse("2026.12.31").unwrap();
+            v.increment_calver("YYYY.MM.DD", time::macros::date!(2027 - 01 - 01))
+                .unwrap();
+            assert_eq!(v, semver::Version::parse("2027.1.1").unwrap());
+        }
+
+        #[test]
+        fn invalid_format() {
+            let today = time::macros::date!(2024 - 03 - 07);
+            let mut v = semver::Version::parse("1.0.0").unwrap();
+            assert!(v.increment_calver("YYYY.MM", today).is_err());
+            assert!(v.increment_calver("YYYY.MICRO.MM", today).is_err());
+            assert!(v.increment_calver("YYYY.MM.NN", today).is_err());
+        }
+    }
+
     mod upgrade_requirement {
         use super::*;
 
@@ -414,5 +672,41 @@ mod test {
             assert_req_bump("1.1.1", "=1.0.0", "=1.1.1");
             assert_req_bump("2.0.0", "=1.0.0", "=2.0.0");
         }
+
+        #[test]
+        fn range_compatible() {
+            assert_req_bump("0.3.0", ">=0.3, <0.5", None);
+            assert_req_bump("0.4.2", ">=0.3, <0.5", None);
+            assert_req_bump("1.4.0", ">=1.2", None);
+            assert_req_bump("1.2.5", ">1.2.3, <=1.3", None);
+        }
+
+        #[test]
+        fn range_incompatible() {
+            assert_req_bump("0.5.0", ">=0.3, <0.5", ">=0.3, <0.6");
+            assert_req_bump("0.5.1", ">=0.3.0, <0.5.0", ">=0.3.0, <0.6.0");
+            assert_req_bump("0.7.0", ">=0.3, <0.5", ">=0.3, <0.8");
+            assert_req_bump("2.1.0", ">=1, <2", ">=1, <3");
+            assert_req_bump("1.2.3", ">=1.2.0, <1.2.3", ">=1.2.0, <1.2.4");
+            assert_req_bump("1.4.1", ">=1.2, <=1.3", ">=1.2, <=1.4");
+            assert_req_bump("0.5.0", "<0.5", "<0.6");
+        }
+
+        #[test]
+        fn range_lower() {
+            assert_req_bump("0.2.1", ">=0.3, <0.5", ">=0.2, <0.5");
+            assert_req_bump("1.2.0", ">1.2", ">=1.2");
+        }
+
+        #[test]
+        fn range_pre_release() {
+            assert_req_bump("0.5.0-rc.1", ">=0.3, <0.5", ">=0.5.0-rc.1, <0.6");
+            assert_req_bump("0.4.0-rc.1", "<0.5", ">=0.4.0-rc.1, <0.5");
+        }
+
+        #[test]
+        fn range_mixed() {
+            assert_req_bump("0.5.0", "^0.3, <0.5", "^0.5, <0.6");
+        }
     }
 }
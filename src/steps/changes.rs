@@ -1,7 +1,9 @@
+use crate::config::CommitRule;
 use crate::error::CargoResult;
 use crate::error::CliError;
 use crate::ops::git;
 use crate::ops::version::VersionExt as _;
+use crate::steps::BumpLevel;
 use crate::steps::plan;
 use clap_cargo::style::{ERROR, GOOD, NOP, WARN};
 
@@ -113,69 +115,7 @@ pub fn changes(
         let crate_name = pkg.meta.name.as_str();
         if let Some(prior_tag_name) = &pkg.prior_tag {
             let workspace_root = ws_meta.workspace_root.as_std_path();
-            let repo = git2::Repository::discover(workspace_root)?;
-
-            let mut tag_id = None;
-            let fq_prior_tag_name = format!("refs/tags/{prior_tag_name}");
-            repo.tag_foreach(|id, name| {
-                if name == fq_prior_tag_name.as_bytes() {
-                    tag_id = Some(id);
-                    false
-                } else {
-                    true
-                }
-            })?;
-            let tag_id =
-                tag_id.ok_or_else(|| anyhow::format_err!("could not find tag {prior_tag_name}"))?;
-
-            let head_id = repo.head()?.peel_to_commit()?.id();
-
-            let mut revwalk = repo.revwalk()?;
-            revwalk.push_range(&format!("{tag_id}..{head_id}"))?;
-
-            let mut commits = Vec::new();
-            for commit_id in revwalk {
-                let commit_id = commit_id?;
-                let commit = repo.find_commit(commit_id)?;
-                if 1 < commit.parent_count() {
-                    // Assuming merge commits can be ignored
-                    continue;
-                }
-                let parent_tree = commit.parent(0).ok().map(|c| c.tree()).transpose()?;
-                let tree = commit.tree()?;
-                let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
-
-                let mut changed_paths = std::collections::BTreeSet::new();
-                for delta in diff.deltas() {
-                    let old_path = delta.old_file().path();
-                    let new_path = delta.new_file().path();
-                    for entry_relpath in [old_path, new_path].into_iter().flatten() {
-                        for path in pkg
-                            .package_content
-                            .iter()
-                            .filter_map(|p| p.strip_prefix(workspace_root).ok())
-                        {
-                            if path == entry_relpath {
-                                changed_paths.insert(path.to_owned());
-                            }
-                        }
-                    }
-                }
-
-                if !changed_paths.is_empty() {
-                    let short_id =
-                        String::from_utf8_lossy(&repo.find_object(commit_id, None)?.short_id()?)
-                            .into_owned();
-                    commits.push(PackageCommit {
-                        id: commit_id,
-                        short_id,
-                        summary: String::from_utf8_lossy(commit.summary_bytes().unwrap_or(b""))
-                            .into_owned(),
-                        message: String::from_utf8_lossy(commit.message_bytes()).into_owned(),
-                        paths: changed_paths,
-                    });
-                }
-            }
+            let commits = package_commits(workspace_root, pkg, prior_tag_name)?;
 
             if !commits.is_empty() {
                 crate::ops::shell::status(
@@ -186,7 +126,6 @@ pub fn changes(
                     ),
                 )?;
                 let prefix = format!("{:>13}", " ");
-                let mut max_status = None;
                 for commit in &commits {
                     let _ = crate::ops::shell::write_stderr(&prefix, &NOP);
                     let _ = crate::ops::shell::write_stderr(&commit.short_id, &WARN);
@@ -196,16 +135,8 @@ pub fn changes(
                     let current_status = commit.status();
                     write_status(current_status);
                     let _ = crate::ops::shell::write_stderr("\n", &NOP);
-                    match (current_status, max_status) {
-                        (Some(cur), Some(max)) => {
-                            max_status = Some(cur.max(max));
-                        }
-                        (Some(s), None) | (None, Some(s)) => {
-                            max_status = Some(s);
-                        }
-                        (None, None) => {}
-                    }
                 }
+                let mut max_status = max_status(&commits);
                 if version.full_version.is_prerelease() {
                     // Enough unknowns about pre-release to not bother
                     max_status = None;
@@ -217,39 +148,7 @@ pub fn changes(
                     .unwrap_or(false);
                 let bumped = !unbumped;
                 if let Some(max_status) = max_status {
-                    let suggested = match max_status {
-                        CommitStatus::Breaking => {
-                            match (
-                                version.full_version.major,
-                                version.full_version.minor,
-                                version.full_version.patch,
-                            ) {
-                                (0, 0, _) if bumped => None,
-                                (0, 0, _) => Some("patch"),
-                                (0, _, 0) if bumped => None,
-                                (0, _, _) => Some("minor"),
-                                (_, 0, 0) if bumped => None,
-                                (_, _, _) => Some("major"),
-                            }
-                        }
-                        CommitStatus::Feature => {
-                            match (
-                                version.full_version.major,
-                                version.full_version.minor,
-                                version.full_version.patch,
-                            ) {
-                                (0, 0, _) if bumped => None,
-                                (0, 0, _) => Some("patch"),
-                                (0, _, _) if bumped => None,
-                                (0, _, _) => Some("patch"),
-                                (_, _, 0) if bumped => None,
-                                (_, _, _) => Some("minor"),
-                            }
-                        }
-                        CommitStatus::Fix if bumped => None,
-                        CommitStatus::Fix => Some("patch"),
-                        CommitStatus::Ignore => None,
-                    };
+                    let suggested = suggested_level(&version.full_version, max_status, bumped);
                     if let Some(suggested) = suggested {
                         let _ = crate::ops::shell::help(format!(
                             "to update the version, run `cargo release version -p {crate_name} {suggested}`"
@@ -271,6 +170,271 @@ pub fn changes(
     Ok(())
 }
 
+/// Add a section for the planned version to the package's changelog
+pub fn changelog(
+    ws_meta: &cargo_metadata::Metadata,
+    pkg: &plan::PackageRelease,
+    dry_run: bool,
+) -> CargoResult<()> {
+    let Some(path) = pkg.config.changelog() else {
+        return Ok(());
+    };
+    let crate_name = pkg.meta.name.as_str();
+    let Some(prior_tag_name) = &pkg.prior_tag else {
+        let _ = crate::ops::shell::warn(format!(
+            "not updating changelog for {crate_name} because no tag was found. Try setting `--prev-tag-name <TAG>`."
+        ));
+        return Ok(());
+    };
+
+    let workspace_root = ws_meta.workspace_root.as_std_path();
+    let commits = package_commits(workspace_root, pkg, prior_tag_name)?;
+    let mut entries = Vec::new();
+    for commit in &commits {
+        entries.extend(commit.changelog_entry());
+    }
+    if entries.is_empty() {
+        log::debug!("no changelog entries for {crate_name} since {prior_tag_name}");
+        return Ok(());
+    }
+
+    let file = pkg.package_root.join(path);
+    if !file.exists() {
+        anyhow::bail!("unable to find changelog {}", file.display());
+    }
+    let version = pkg.planned_version.as_ref().unwrap_or(&pkg.initial_version);
+    let section = crate::ops::changelog::render_section(
+        &version.bare_version_string,
+        crate::ops::replace::NOW.as_str(),
+        &entries,
+    );
+    let data = std::fs::read_to_string(&file)?;
+    let updated = crate::ops::changelog::insert_section(&data, &section)
+        .map_err(|e| anyhow::format_err!("failed to update {}: {e}", file.display()))?;
+
+    if dry_run {
+        let _ = crate::ops::shell::status(
+            "Updating",
+            format!(
+                "{}\n{}",
+                path.display(),
+                crate::ops::diff::unified_diff(&data, &updated, path, "updated")
+            ),
+        );
+    } else {
+        std::fs::write(&file, updated)?;
+    }
+
+    Ok(())
+}
+
+/// Commits since `prior_tag_name` that touch the content of `pkg`
+pub fn package_commits(
+    workspace_root: &std::path::Path,
+    pkg: &plan::PackageRelease,
+    prior_tag_name: &str,
+) -> CargoResult<Vec<PackageCommit>> {
+    let rules = compile_rules(pkg.config.commit_rules())?;
+    let repo = git2::Repository::discover(workspace_root)?;
+
+    let mut tag_id = None;
+    let fq_prior_tag_name = format!("refs/tags/{prior_tag_name}");
+    repo.tag_foreach(|id, name| {
+        if name == fq_prior_tag_name.as_bytes() {
+            tag_id = Some(id);
+            false
+        } else {
+            true
+        }
+    })?;
+    let tag_id =
+        tag_id.ok_or_else(|| anyhow::format_err!("could not find tag {prior_tag_name}"))?;
+
+    let head_id = repo.head()?.peel_to_commit()?.id();
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_range(&format!("{tag_id}..{head_id}"))?;
+
+    let mut commits = Vec::new();
+    for commit_id in revwalk {
+        let commit_id = commit_id?;
+        let commit = repo.find_commit(commit_id)?;
+        if 1 < commit.parent_count() {
+            // Assuming merge commits can be ignored
+            continue;
+        }
+        let parent_tree = commit.parent(0).ok().map(|c| c.tree()).transpose()?;
+        let tree = commit.tree()?;
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+
+        let mut changed_paths = std::collections::BTreeSet::new();
+        for delta in diff.deltas() {
+            let old_path = delta.old_file().path();
+            let new_path = delta.new_file().path();
+            for entry_relpath in [old_path, new_path].into_iter().flatten() {
+                for path in pkg
+                    .package_content
+                    .iter()
+                    .filter_map(|p| p.strip_prefix(workspace_root).ok())
+                {
+                    if path == entry_relpath {
+                        changed_paths.insert(path.to_owned());
+                    }
+                }
+            }
+        }
+
+        if !changed_paths.is_empty() {
+            let short_id = String::from_utf8_lossy(&repo.find_object(commit_id, None)?.short_id()?)
+                .into_owned();
+            let summary =
+                String::from_utf8_lossy(commit.summary_bytes().unwrap_or(b"")).into_owned();
+            let message = String::from_utf8_lossy(commit.message_bytes()).into_owned();
+            let rule_status = rule_status(&rules, &summary, &message)?;
+            commits.push(PackageCommit {
+                id: commit_id,
+                short_id,
+                summary,
+                message,
+                paths: changed_paths,
+                rule_status,
+            });
+        }
+    }
+
+    Ok(commits)
+}
+
+/// A [`CommitRule`] with its patterns compiled
+struct CompiledRule<'r> {
+    rule: &'r CommitRule,
+    summary: Option<regex::Regex>,
+    trailer: Option<regex::Regex>,
+}
+
+fn compile_rules(rules: &[CommitRule]) -> CargoResult<Vec<CompiledRule<'_>>> {
+    rules
+        .iter()
+        .map(|rule| {
+            let summary = rule
+                .summary
+                .as_deref()
+                .map(|summary| {
+                    regex::Regex::new(summary).map_err(|e| {
+                        anyhow::format_err!("invalid `commit-rules` summary `{summary}`: {e}")
+                    })
+                })
+                .transpose()?;
+            let trailer = rule
+                .trailer
+                .as_deref()
+                .map(|trailer| {
+                    regex::Regex::new(trailer).map_err(|e| {
+                        anyhow::format_err!("invalid `commit-rules` trailer `{trailer}`: {e}")
+                    })
+                })
+                .transpose()?;
+            Ok(CompiledRule {
+                rule,
+                summary,
+                trailer,
+            })
+        })
+        .collect()
+}
+
+/// The status from the first of `rules` to match the commit
+fn rule_status(
+    rules: &[CompiledRule<'_>],
+    summary: &str,
+    message: &str,
+) -> CargoResult<Option<CommitStatus>> {
+    if rules.is_empty() {
+        return Ok(None);
+    }
+
+    let parts = git_conventional::Commit::parse(message).ok();
+    for compiled in rules {
+        let rule = compiled.rule;
+        if let Some(type_) = rule.type_.as_deref() {
+            let matched = parts
+                .as_ref()
+                .map(|p| p.type_().as_str().eq_ignore_ascii_case(type_))
+                .unwrap_or(false);
+            if !matched {
+                continue;
+            }
+        }
+        if let Some(scope) = rule.scope.as_deref() {
+            let matched = parts
+                .as_ref()
+                .and_then(|p| p.scope())
+                .map(|s| s.as_str().eq_ignore_ascii_case(scope))
+                .unwrap_or(false);
+            if !matched {
+                continue;
+            }
+        }
+        if let Some(pattern) = compiled.summary.as_ref()
+            && !pattern.is_match(summary)
+        {
+            continue;
+        }
+        if let Some(pattern) = compiled.trailer.as_ref() {
+            let trailers = git2::message_trailers_strs(message)?;
+            let matched = trailers
+                .iter()
+                .any(|(token, value)| pattern.is_match(&format!("{token}: {value}")));
+            if !matched {
+                continue;
+            }
+        }
+        return Ok(Some(rule.status));
+    }
+
+    Ok(None)
+}
+
+/// The most significant change among `commits`
+pub fn max_status(commits: &[PackageCommit]) -> Option<CommitStatus> {
+    let mut max_status = None;
+    for commit in commits {
+        max_status = max_status.max(commit.status());
+    }
+    max_status
+}
+
+/// Bump level needed to release a `max_status` change on top of `version`
+///
+/// `bumped` is whether `version` has already been bumped since the last release.
+pub fn suggested_level(
+    version: &semver::Version,
+    max_status: CommitStatus,
+    bumped: bool,
+) -> Option<BumpLevel> {
+    match max_status {
+        CommitStatus::Breaking => match (version.major, version.minor, version.patch) {
+            (0, 0, _) if bumped => None,
+            (0, 0, _) => Some(BumpLevel::Patch),
+            (0, _, 0) if bumped => None,
+            (0, _, _) => Some(BumpLevel::Minor),
+            (_, 0, 0) if bumped => None,
+            (_, _, _) => Some(BumpLevel::Major),
+        },
+        CommitStatus::Feature => match (version.major, version.minor, version.patch) {
+            (0, 0, _) if bumped => None,
+            (0, 0, _) => Some(BumpLevel::Patch),
+            (0, _, _) if bumped => None,
+            (0, _, _) => Some(BumpLevel::Patch),
+            (_, _, 0) if bumped => None,
+            (_, _, _) => Some(BumpLevel::Minor),
+        },
+        CommitStatus::Fix if bumped => None,
+        CommitStatus::Fix => Some(BumpLevel::Patch),
+        CommitStatus::Ignore => None,
+    }
+}
+
 fn write_status(status: Option<CommitStatus>) {
     if let Some(status) = status {
         let suffix;
@@ -303,10 +467,21 @@ pub struct PackageCommit {
     pub summary: String,
     pub message: String,
     pub paths: std::collections::BTreeSet<std::path::PathBuf>,
+    /// The status from the first of the package's `commit-rules` to match
+    pub rule_status: Option<CommitStatus>,
 }
 
 impl PackageCommit {
     pub fn status(&self) -> Option<CommitStatus> {
+        let parts = git_conventional::Commit::parse(&self.message).ok();
+        if parts.as_ref().map(|p| p.breaking()).unwrap_or(false) {
+            return Some(CommitStatus::Breaking);
+        }
+
+        if let Some(status) = self.rule_status {
+            return Some(status);
+        }
+
         if let Some(status) = self.conventional_status() {
             return status;
         }
@@ -314,6 +489,65 @@ impl PackageCommit {
         None
     }
 
+    pub fn changelog_entry(&self) -> Option<crate::ops::changelog::Entry<'_>> {
+        use crate::ops::changelog::{Entry, Group};
+
+        let parts = git_conventional::Commit::parse(&self.message).ok();
+        let scope = parts.as_ref().and_then(|p| p.scope()).map(|s| s.as_str());
+        let description = parts
+            .as_ref()
+            .map(|p| p.description())
+            .unwrap_or(&self.summary);
+        if !parts.as_ref().map(|p| p.breaking()).unwrap_or(false)
+            && let Some(status) = self.rule_status
+        {
+            let group = match status {
+                CommitStatus::Breaking => Group::Breaking,
+                CommitStatus::Feature => Group::Features,
+                CommitStatus::Fix => Group::Fixes,
+                CommitStatus::Ignore => return None,
+            };
+            return Some(Entry {
+                group,
+                scope,
+                description,
+            });
+        }
+
+        let Some(parts) = parts else {
+            return Some(Entry {
+                group: Group::Other,
+                scope: None,
+                description: &self.summary,
+            });
+        };
+        let group = if parts.breaking() {
+            Group::Breaking
+        } else if parts.type_() == git_conventional::Type::FEAT {
+            Group::Features
+        } else if parts.type_() == git_conventional::Type::FIX {
+            Group::Fixes
+        } else if parts.type_() == git_conventional::Type::PERF {
+            Group::Performance
+        } else if parts.type_() == git_conventional::Type::DOCS {
+            Group::Documentation
+        } else {
+            return None;
+        };
+        let description = if group == Group::Breaking {
+            parts
+                .breaking_description()
+                .unwrap_or_else(|| parts.description())
+        } else {
+            parts.description()
+        };
+        Some(Entry {
+            group,
+            scope,
+            description,
+        })
+    }
+
     fn conventional_status(&self) -> Option<Option<CommitStatus>> {
         let parts = git_conventional::Commit::parse(&self.message).ok()?;
         if parts.breaking() {
@@ -346,10 +580,262 @@ impl PackageCommit {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
 pub enum CommitStatus {
     Ignore,
     Fix,
     Feature,
     Breaking,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod suggested_level {
+        use super::*;
+
+        fn level(version: &str, status: CommitStatus, bumped: bool) -> Option<BumpLevel> {
+            suggested_level(&semver::Version::parse(version).unwrap(), status, bumped)
+        }
+
+        #[test]
+        fn unbumped() {
+            assert_eq!(
+                level("1.2.3", CommitStatus::Breaking, false),
+                Some(BumpLevel::Major)
+            );
+            assert_eq!(
+                level("1.2.3", CommitStatus::Feature, false),
+                Some(BumpLevel::Minor)
+            );
+            assert_eq!(
+                level("1.2.3", CommitStatus::Fix, false),
+                Some(BumpLevel::Patch)
+            );
+            assert_eq!(level("1.2.3", CommitStatus::Ignore, false), None);
+        }
+
+        #[test]
+        fn before_1_0() {
+            assert_eq!(
+                level("0.2.3", CommitStatus::Breaking, false),
+                Some(BumpLevel::Minor)
+            );
+            assert_eq!(
+                level("0.2.3", CommitStatus::Feature, false),
+                Some(BumpLevel::Patch)
+            );
+            assert_eq!(
+                level("0.0.3", CommitStatus::Breaking, false),
+                Some(BumpLevel::Patch)
+            );
+        }
+
+        #[test]
+        fn already_bumped() {
+            assert_eq!(level("2.0.0", CommitStatus::Breaking, true), None);
+            assert_eq!(level("1.3.0", CommitStatus::Feature, true), None);
+            assert_eq!(level("1.2.4", CommitStatus::Fix, true), None);
+            // The bump so far is too small for the change
+            assert_eq!(
+                level("1.2.4", CommitStatus::Feature, true),
+                Some(BumpLevel::Minor)
+            );
+            assert_eq!(
+                level("1.3.0", CommitStatus::Breaking, true),
+                Some(BumpLevel::Major)
+            );
+        }
+    }
+
+    mod package_commits {
+        use super::*;
+
+        use crate::steps::fixture::{Workspace, id};
+
+        fn commit(ws: &Workspace, message: &str) {
+            ws.write("foo/src/lib.rs", &format!("// {message}\n"));
+            ws.commit(message);
+        }
+
+        fn statuses(ws: &Workspace) -> Vec<(String, Option<CommitStatus>)> {
+            let ws_meta = ws.metadata();
+            let pkgs = ws.load(&ws_meta);
+            let pkg = &pkgs[id(&pkgs, "foo")];
+            let mut commits = package_commits(ws.root(), pkg, "v0.1.0").unwrap();
+            commits.reverse();
+            commits
+                .iter()
+                .map(|c| (c.summary.clone(), c.status()))
+                .collect()
+        }
+
+        #[test]
+        fn rules_override_defaults() {
+            let ws = Workspace::new(
+                r#"
+commit-rules = [
+    { type = "fix", scope = "deps", status = "ignore" },
+    { summary = "^chore: Bump", status = "fix" },
+    { trailer = "^Release-Note: feature", status = "feature" },
+    { type = "feat", status = "ignore" },
+]
+"#,
+                &[("foo", "0.1.0", &[])],
+            );
+            ws.tag("v0.1.0");
+            commit(&ws, "fix(deps): Update regex");
+            commit(&ws, "fix: Handle empty input");
+            commit(&ws, "chore: Bump MSRV");
+            commit(&ws, "docs: Explain config\n\nRelease-Note: feature");
+            commit(&ws, "feat!: Drop the old API");
+
+            assert_eq!(
+                statuses(&ws),
+                [
+                    (
+                        "fix(deps): Update regex".to_owned(),
+                        Some(CommitStatus::Ignore)
+                    ),
+                    (
+                        "fix: Handle empty input".to_owned(),
+                        Some(CommitStatus::Fix)
+                    ),
+                    ("chore: Bump MSRV".to_owned(), Some(CommitStatus::Fix)),
+                    (
+                        "docs: Explain config".to_owned(),
+                        Some(CommitStatus::Feature)
+                    ),
+                    // Breaking changes can't be overridden
+                    (
+                        "feat!: Drop the old API".to_owned(),
+                        Some(CommitStatus::Breaking)
+                    ),
+                ]
+            );
+        }
+
+        #[test]
+        fn ignored_left_out_of_changelog() {
+            let ws = Workspace::new(
+                r#"commit-rules = [{ scope = "deps", status = "ignore" }]"#,
+                &[("foo", "0.1.0", &[])],
+            );
+            ws.tag("v0.1.0");
+            commit(&ws, "fix(deps): Update regex");
+            commit(&ws, "fix: Handle empty input");
+
+            let ws_meta = ws.metadata();
+            let pkgs = ws.load(&ws_meta);
+            let commits = package_commits(ws.root(), &pkgs[id(&pkgs, "foo")], "v0.1.0").unwrap();
+            let entries = commits
+                .iter()
+                .filter_map(|c| c.changelog_entry())
+                .map(|e| e.description)
+                .collect::<Vec<_>>();
+            assert_eq!(entries, ["Handle empty input"]);
+        }
+
+        #[test]
+        fn invalid_rule() {
+            let ws = Workspace::new(
+                r#"commit-rules = [{ summary = "(", status = "fix" }]"#,
+                &[("foo", "0.1.0", &[])],
+            );
+            ws.tag("v0.1.0");
+
+            let ws_meta = ws.metadata();
+            let pkgs = ws.load(&ws_meta);
+            let err = package_commits(ws.root(), &pkgs[id(&pkgs, "foo")], "v0.1.0")
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("invalid `commit-rules` summary `(`"), "{err}");
+        }
+    }
+
+    mod changelog {
+        use super::*;
+
+        use crate::steps::fixture::Workspace;
+
+        const CHANGELOG: &str = "# Changelog
+
+## [Unreleased] - ReleaseDate
+
+## [0.1.0] - 2022-01-01
+
+- Initial release
+";
+
+        /// `foo` with a fix since its last release
+        fn workspace(changelog: Option<&str>) -> Workspace {
+            let ws = Workspace::new(r#"changelog = "CHANGELOG.md""#, &[("foo", "0.1.0", &[])]);
+            if let Some(changelog) = changelog {
+                ws.write("foo/CHANGELOG.md", changelog);
+            }
+            ws.commit("chore: Add changelog");
+            ws.tag("foo-v0.1.0");
+            ws.write("foo/src/lib.rs", "// fixed\n");
+            ws.commit("fix: Handle empty input");
+            ws
+        }
+
+        fn run(ws: &Workspace, dry_run: bool) -> CargoResult<()> {
+            let ws_meta = ws.metadata();
+            let pkgs = ws.plan(&ws_meta, BumpLevel::Patch);
+            changelog(&ws_meta, &pkgs[0], dry_run)
+        }
+
+        #[test]
+        fn below_unreleased() {
+            let ws = workspace(Some(CHANGELOG));
+            run(&ws, false).unwrap();
+            assert_eq!(
+                ws.read("foo/CHANGELOG.md"),
+                format!(
+                    "# Changelog
+
+## [Unreleased] - ReleaseDate
+
+## [0.1.1] - {}
+
+### Fixes
+
+- Handle empty input
+
+## [0.1.0] - 2022-01-01
+
+- Initial release
+",
+                    crate::ops::replace::NOW.as_str()
+                )
+            );
+        }
+
+        #[test]
+        fn dry_run() {
+            let ws = workspace(Some(CHANGELOG));
+            run(&ws, true).unwrap();
+            assert_eq!(ws.read("foo/CHANGELOG.md"), CHANGELOG);
+        }
+
+        #[test]
+        fn missing_file() {
+            let ws = workspace(None);
+            let err = run(&ws, false).unwrap_err().to_string();
+            assert!(err.starts_with("unable to find changelog"), "{err}");
+        }
+
+        #[test]
+        fn no_prior_tag() {
+            let ws = workspace(Some(CHANGELOG));
+            ws.git(&["tag", "-d", "foo-v0.1.0"]);
+            run(&ws, false).unwrap();
+            assert_eq!(ws.read("foo/CHANGELOG.md"), CHANGELOG);
+        }
+    }
+}
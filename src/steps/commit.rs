@@ -172,7 +172,18 @@ pub fn workspace_commit(
             date: Some(NOW.as_str()),
             ..Default::default()
         };
-        template.render(ws_config.pre_release_commit_message())
+        // A release of a single group uses the group's message
+        let mut group = pkgs.first().and_then(|pkg| pkg.group.as_deref());
+        for pkg in pkgs {
+            if pkg.group.as_deref() != group {
+                group = None;
+                break;
+            }
+        }
+        let commit_msg = group
+            .and_then(|name| ws_config.groups().get(name))
+            .and_then(|group| group.pre_release_commit_message.as_deref());
+        template.render(commit_msg.unwrap_or_else(|| ws_config.pre_release_commit_message()))
     };
     if !git::commit_all(
         ws_meta.workspace_root.as_std_path(),
@@ -38,8 +38,14 @@ impl ConfigStep {
                     .find(|p| p.id == *root_id)
                     .expect("root should always be present");
 
+                let ws_config = load_workspace_config(&self.config, &ws_meta)?;
+                let groups = ws_config.group_members([pkg.name.as_str()])?;
+                let group = groups
+                    .get(pkg.name.as_str())
+                    .map(|name| (*name, &ws_config.groups()[*name]));
+
                 let mut release_config = Config::from_defaults();
-                release_config.update(&load_package_config(&self.config, &ws_meta, pkg)?);
+                release_config.update(&load_package_config(&self.config, &ws_meta, pkg, group)?);
                 release_config
             } else {
                 let mut release_config = Config::from_defaults();
@@ -0,0 +1,149 @@
+//! Workspaces in git repos for testing steps
+
+use std::path::Path;
+
+use assert_fs::prelude::*;
+
+use crate::steps::plan;
+
+/// A workspace member: name, version, and normal dependencies on other members with their
+/// version requirement
+pub(crate) type Member<'a> = (&'a str, &'a str, &'a [(&'a str, &'a str)]);
+
+pub(crate) struct Workspace {
+    pub(crate) temp: assert_fs::TempDir,
+}
+
+impl Workspace {
+    /// Commit a workspace of `members` with `release` as its `release.toml`
+    pub(crate) fn new(release: &str, members: &[Member<'_>]) -> Self {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let names = members
+            .iter()
+            .map(|(name, _, _)| format!("{name:?}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        temp.child("Cargo.toml")
+            .write_str(&format!(
+                "[workspace]\nmembers = [{names}]\nresolver = \"2\"\n"
+            ))
+            .unwrap();
+        temp.child("release.toml").write_str(release).unwrap();
+        temp.child(".gitignore").write_str("/target\n").unwrap();
+        for (name, version, deps) in members {
+            let deps = deps
+                .iter()
+                .map(|(dep, req)| format!("{dep} = {{ version = {req:?}, path = \"../{dep}\" }}\n"))
+                .collect::<String>();
+            temp.child(format!("{name}/Cargo.toml"))
+                .write_str(&format!(
+                    "[package]\nname = {name:?}\nversion = {version:?}\nedition = \"2021\"\nlicense = \"MIT\"\ndescription = \"test\"\nrepository = \"https://example.com\"\n\n[dependencies]\n{deps}"
+                ))
+                .unwrap();
+            temp.child(format!("{name}/src/lib.rs"))
+                .write_str("")
+                .unwrap();
+        }
+
+        let ws = Self { temp };
+        ws.git(&["init", "--quiet", "--initial-branch=main"]);
+        ws.git(&["config", "user.name", "Test"]);
+        ws.git(&["config", "user.email", "test@example.com"]);
+        ws.git(&["config", "commit.gpgsign", "false"]);
+        ws.git(&["config", "tag.gpgsign", "false"]);
+        ws.commit("chore: Initial");
+        ws
+    }
+
+    pub(crate) fn root(&self) -> &Path {
+        self.temp.path()
+    }
+
+    pub(crate) fn git(&self, args: &[&str]) -> String {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(self.root())
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    pub(crate) fn write(&self, path: &str, content: &str) {
+        self.temp.child(path).write_str(content).unwrap();
+    }
+
+    pub(crate) fn read(&self, path: &str) -> String {
+        std::fs::read_to_string(self.root().join(path)).unwrap()
+    }
+
+    pub(crate) fn commit(&self, msg: &str) {
+        self.git(&["add", "--all"]);
+        self.git(&["commit", "--quiet", "--allow-empty", "-m", msg]);
+    }
+
+    pub(crate) fn tag(&self, name: &str) {
+        self.git(&["tag", "-a", "-m", name, name]);
+    }
+
+    pub(crate) fn metadata(&self) -> cargo_metadata::Metadata {
+        cargo_metadata::MetadataCommand::new()
+            .manifest_path(self.root().join("Cargo.toml"))
+            .features(cargo_metadata::CargoOpt::AllFeatures)
+            .exec()
+            .unwrap()
+    }
+
+    /// Load the workspace, ignoring the user's configuration
+    pub(crate) fn load(
+        &self,
+        ws_meta: &cargo_metadata::Metadata,
+    ) -> indexmap::IndexMap<cargo_metadata::PackageId, plan::PackageRelease> {
+        plan::load(&self.config_args(), ws_meta).unwrap()
+    }
+
+    /// Plan releasing every package with a `level` bump
+    pub(crate) fn plan(
+        &self,
+        ws_meta: &cargo_metadata::Metadata,
+        level: crate::steps::BumpLevel,
+    ) -> Vec<plan::PackageRelease> {
+        let level = crate::steps::TargetVersion::Relative(level);
+        let mut pkgs = self.load(ws_meta);
+        for pkg in pkgs.values_mut() {
+            if pkg.config.release() {
+                pkg.bump(ws_meta, &level, None).unwrap();
+            }
+        }
+        plan::plan(pkgs)
+            .unwrap()
+            .into_values()
+            .filter(|pkg| pkg.config.release())
+            .collect()
+    }
+
+    /// Only the workspace's `release.toml`, nothing from `$HOME`
+    pub(crate) fn config_args(&self) -> crate::config::ConfigArgs {
+        crate::config::ConfigArgs {
+            custom_config: Some(self.root().join("release.toml")),
+            isolated: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// The ID of the package named `name`
+pub(crate) fn id<'p>(
+    pkgs: &'p indexmap::IndexMap<cargo_metadata::PackageId, plan::PackageRelease>,
+    name: &str,
+) -> &'p cargo_metadata::PackageId {
+    pkgs.values()
+        .find(|pkg| pkg.meta.name.as_str() == name)
+        .map(|pkg| &pkg.meta.id)
+        .unwrap()
+}
@@ -1,7 +1,8 @@
 use std::ffi::OsStr;
 use std::path::Path;
 
-use crate::error::CliError;
+use crate::config::{Command, Config};
+use crate::error::{CargoResult, CliError};
 use crate::ops::cmd;
 use crate::ops::git;
 use crate::ops::replace::{NOW, Template};
@@ -16,10 +17,17 @@ pub struct HookStep {
     #[command(flatten)]
     workspace: clap_cargo::Workspace,
 
+    #[command(flatten)]
+    group: super::GroupArgs,
+
     /// Process all packages whose current version is unpublished
     #[arg(long)]
     unpublished: bool,
 
+    /// Process packages changed since their last release
+    #[arg(long, conflicts_with = "unpublished")]
+    changed: bool,
+
     /// Custom config file
     #[arg(short, long = "config", value_name = "PATH")]
     custom_config: Option<std::path::PathBuf>,
@@ -66,14 +74,16 @@ impl HookStep {
             .exec()?;
         let config = self.to_config();
         let ws_config = crate::config::load_workspace_config(&config, &ws_meta)?;
+        let workspace = self.group.select(&self.workspace, &ws_config, &ws_meta)?;
         let mut pkgs = plan::load(&config, &ws_meta)?;
 
-        let (_selected_pkgs, excluded_pkgs) =
-            if self.unpublished && self.workspace == clap_cargo::Workspace::default() {
-                ws_meta.packages.iter().partition(|_| false)
-            } else {
-                self.workspace.partition_packages(&ws_meta)
-            };
+        let excluded_pkgs = super::deselected_packages(
+            &ws_meta,
+            &workspace,
+            &pkgs,
+            self.changed,
+            self.unpublished && workspace == clap_cargo::Workspace::default(),
+        );
         for excluded_pkg in excluded_pkgs {
             let Some(pkg) = pkgs.get_mut(&excluded_pkg.id) else {
                 // Either not in workspace or marked as `release = false`.
@@ -84,7 +94,7 @@ impl HookStep {
             }
 
             let crate_name = pkg.meta.name.as_str();
-            let explicitly_excluded = self.workspace.exclude.contains(&excluded_pkg.name);
+            let explicitly_excluded = workspace.exclude.contains(&excluded_pkg.name);
             // 1. Don't show this message if already not releasing in config
             // 2. Still respect `--exclude`
             if pkg.config.release()
@@ -115,7 +125,7 @@ impl HookStep {
 
         let pkgs = plan::plan(pkgs)?;
 
-        let (selected_pkgs, _excluded_pkgs): (Vec<_>, Vec<_>) = pkgs
+        let (selected_pkgs, excluded_pkgs): (Vec<_>, Vec<_>) = pkgs
             .into_iter()
             .map(|(_, pkg)| pkg)
             .partition(|p| p.config.release());
@@ -152,11 +162,20 @@ impl HookStep {
 
         // STEP 1: Release Confirmation
         super::confirm("Bump", &selected_pkgs, self.no_confirm, dry_run)?;
+        let plan_file = plan::Report::new(&selected_pkgs, &excluded_pkgs, &plan::Checks::default())
+            .persist(&ws_meta)?;
 
         // STEP 2: update current version, save and commit
         for pkg in &selected_pkgs {
-            hook(&ws_meta, pkg, dry_run)?;
+            hook(&ws_meta, plan_file.path(), pkg, dry_run)?;
         }
+        workspace_pre_release_hook(
+            &ws_meta,
+            plan_file.path(),
+            &ws_config,
+            &selected_pkgs,
+            dry_run,
+        )?;
 
         super::finish(failed, dry_run)
     }
@@ -174,52 +193,445 @@ impl HookStep {
 
 pub fn hook(
     ws_meta: &cargo_metadata::Metadata,
+    plan_path: &Path,
     pkg: &plan::PackageRelease,
     dry_run: bool,
 ) -> Result<(), CliError> {
-    if let Some(pre_rel_hook) = pkg.config.pre_release_hook() {
-        let cwd = &pkg.package_root;
-        let crate_name = pkg.meta.name.as_str();
-        let version = pkg.planned_version.as_ref().unwrap_or(&pkg.initial_version);
-        let prev_version_var = pkg.initial_version.bare_version_string.as_str();
-        let prev_metadata_var = pkg.initial_version.full_version.build.as_str();
-        let version_var = version.bare_version_string.as_str();
-        let metadata_var = version.full_version.build.as_str();
-        let template = Template {
-            prev_version: Some(prev_version_var),
-            prev_metadata: Some(prev_metadata_var),
-            version: Some(version_var),
-            metadata: Some(metadata_var),
-            crate_name: Some(crate_name),
-            date: Some(NOW.as_str()),
-            tag_name: pkg.planned_tag.as_deref(),
-            ..Default::default()
-        };
-        let pre_rel_hook = pre_rel_hook
-            .args()
-            .into_iter()
-            .map(|arg| template.render(arg))
-            .collect::<Vec<_>>();
-        log::debug!("calling pre-release hook: {pre_rel_hook:?}");
-        let envs = maplit::btreemap! {
-            OsStr::new("PREV_VERSION") => prev_version_var.as_ref(),
-            OsStr::new("PREV_METADATA") => prev_metadata_var.as_ref(),
-            OsStr::new("NEW_VERSION") => version_var.as_ref(),
-            OsStr::new("NEW_METADATA") => metadata_var.as_ref(),
-            OsStr::new("DRY_RUN") => OsStr::new(if dry_run { "true" } else { "false" }),
-            OsStr::new("CRATE_NAME") => OsStr::new(crate_name),
-            OsStr::new("WORKSPACE_ROOT") => ws_meta.workspace_root.as_os_str(),
-            OsStr::new("CRATE_ROOT") => pkg.manifest_path.parent().unwrap_or_else(|| Path::new(".")).as_os_str(),
+    if let Some(pre_rel_hook) = pkg.config.hook(HookPoint::PreRelease) {
+        package_hook(
+            ws_meta,
+            plan_path,
+            pkg,
+            "pre-release",
+            pre_rel_hook,
+            &[],
+            dry_run,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Run the `name` hook for `pkg`, with `extra_envs` in addition to the common environment
+///
+/// `plan_path` is where the [`plan::Report`] was written, see [`plan::Report::persist`].
+pub fn package_hook(
+    ws_meta: &cargo_metadata::Metadata,
+    plan_path: &Path,
+    pkg: &plan::PackageRelease,
+    name: &str,
+    command: &Command,
+    extra_envs: &[(&str, &str)],
+    dry_run: bool,
+) -> Result<(), CliError> {
+    let cwd = &pkg.package_root;
+    let crate_name = pkg.meta.name.as_str();
+    let version = pkg.planned_version.as_ref().unwrap_or(&pkg.initial_version);
+    let prev_version_var = pkg.initial_version.bare_version_string.as_str();
+    let prev_metadata_var = pkg.initial_version.full_version.build.as_str();
+    let version_var = version.bare_version_string.as_str();
+    let metadata_var = version.full_version.build.as_str();
+    let template = Template {
+        prev_version: Some(prev_version_var),
+        prev_metadata: Some(prev_metadata_var),
+        version: Some(version_var),
+        metadata: Some(metadata_var),
+        crate_name: Some(crate_name),
+        date: Some(NOW.as_str()),
+        tag_name: pkg.planned_tag.as_deref(),
+        ..Default::default()
+    };
+    let args = command
+        .args()
+        .into_iter()
+        .map(|arg| template.render(arg))
+        .collect::<Vec<_>>();
+    log::debug!("calling {name} hook: {args:?}");
+    let mut envs = maplit::btreemap! {
+        OsStr::new("PREV_VERSION") => prev_version_var.as_ref(),
+        OsStr::new("PREV_METADATA") => prev_metadata_var.as_ref(),
+        OsStr::new("NEW_VERSION") => version_var.as_ref(),
+        OsStr::new("NEW_METADATA") => metadata_var.as_ref(),
+        OsStr::new("DRY_RUN") => OsStr::new(if dry_run { "true" } else { "false" }),
+        OsStr::new("CRATE_NAME") => OsStr::new(crate_name),
+        OsStr::new("WORKSPACE_ROOT") => ws_meta.workspace_root.as_os_str(),
+        OsStr::new("CRATE_ROOT") => pkg.manifest_path.parent().unwrap_or_else(|| Path::new(".")).as_os_str(),
+        OsStr::new("RELEASE_PLAN") => plan_path.as_os_str(),
+    };
+    envs.extend(
+        extra_envs
+            .iter()
+            .map(|(key, value)| (OsStr::new(*key), OsStr::new(*value))),
+    );
+    // we use dry_run environmental variable to run the script
+    // so here we always execute the command.
+    let context = format!(
+        "{name} hook for {crate_name} {}",
+        version.full_version_string
+    );
+    if !call_hook(command, args, envs, cwd, &context)? {
+        let _ = crate::ops::shell::error(format!(
+            "release of {crate_name} aborted by non-zero return of {name} hook."
+        ));
+        return Err(101.into());
+    }
+
+    Ok(())
+}
+
+/// Run the workspace's `name` hook once for all of `pkgs`, with `extra_envs` in addition to the
+/// common environment
+pub fn workspace_hook(
+    ws_meta: &cargo_metadata::Metadata,
+    plan_path: &Path,
+    pkgs: &[plan::PackageRelease],
+    name: &str,
+    command: &Command,
+    extra_envs: &[(&str, &str)],
+    dry_run: bool,
+) -> Result<(), CliError> {
+    let cwd = ws_meta.workspace_root.as_std_path();
+    let shared_version = super::find_shared_versions(pkgs)?;
+    let version_var = shared_version
+        .as_ref()
+        .map(|v| v.bare_version_string.as_str());
+    let metadata_var = shared_version
+        .as_ref()
+        .map(|v| v.full_version.build.as_str());
+    let template = Template {
+        version: version_var,
+        metadata: metadata_var,
+        date: Some(NOW.as_str()),
+        ..Default::default()
+    };
+    let args = command
+        .args()
+        .into_iter()
+        .map(|arg| template.render(arg))
+        .collect::<Vec<_>>();
+    log::debug!("calling workspace {name} hook: {args:?}");
+    let mut envs = maplit::btreemap! {
+        OsStr::new("DRY_RUN") => OsStr::new(if dry_run { "true" } else { "false" }),
+        OsStr::new("WORKSPACE_ROOT") => ws_meta.workspace_root.as_os_str(),
+        OsStr::new("RELEASE_PLAN") => plan_path.as_os_str(),
+    };
+    if let (Some(version_var), Some(metadata_var)) = (version_var, metadata_var) {
+        envs.insert(OsStr::new("NEW_VERSION"), OsStr::new(version_var));
+        envs.insert(OsStr::new("NEW_METADATA"), OsStr::new(metadata_var));
+    }
+    envs.extend(
+        extra_envs
+            .iter()
+            .map(|(key, value)| (OsStr::new(*key), OsStr::new(*value))),
+    );
+    if !call_hook(command, args, envs, cwd, &format!("workspace {name} hook"))? {
+        let _ = crate::ops::shell::error(format!(
+            "release aborted by non-zero return of workspace {name} hook."
+        ));
+        return Err(101.into());
+    }
+
+    Ok(())
+}
+
+/// Run `args` for `command`, retrying per its settings, and report failures with `context`
+fn call_hook(
+    command: &Command,
+    args: Vec<String>,
+    envs: std::collections::BTreeMap<&OsStr, &OsStr>,
+    cwd: &Path,
+    context: &str,
+) -> CargoResult<bool> {
+    let attempts = command.retries() + 1;
+    // Output is only worth repeating when retries or a timeout may have buried it
+    let capture = 1 < attempts;
+    for attempt in 1..=attempts {
+        let output = cmd::call_with_timeout(
+            args.clone(),
+            envs.clone(),
+            Some(cwd),
+            command.timeout(),
+            capture,
+        )?;
+        if output.success() {
+            return Ok(true);
+        }
+
+        let reason = match (output.timed_out, command.timeout(), output.status) {
+            (true, Some(timeout), _) => format!("timed out after {timeout:?}"),
+            (_, _, Some(status)) => format!("failed with {status}"),
+            (_, _, None) => "failed".to_owned(),
         };
-        // we use dry_run environmental variable to run the script
-        // so here we set dry_run=false and always execute the command.
-        if !cmd::call_with_env(pre_rel_hook, envs, cwd, false)? {
-            let _ = crate::ops::shell::error(format!(
-                "release of {crate_name} aborted by non-zero return of prerelease hook."
+        if attempt < attempts {
+            let delay = command.retry_delay();
+            let _ = crate::ops::shell::warn(format!(
+                "{context} {reason}, retrying in {delay:?} (attempt {} of {attempts})",
+                attempt + 1
             ));
-            return Err(101.into());
+            std::thread::sleep(delay);
+        } else {
+            let _ = crate::ops::shell::error(format!("{context} {reason}"));
+            let output = output.output.trim_end();
+            if !output.is_empty() {
+                let _ = crate::ops::shell::note(format!("output of {context}:\n{output}"));
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Run the workspace's pre-release hook once for all of `pkgs`
+pub fn workspace_pre_release_hook(
+    ws_meta: &cargo_metadata::Metadata,
+    plan_path: &Path,
+    ws_config: &Config,
+    pkgs: &[plan::PackageRelease],
+    dry_run: bool,
+) -> Result<(), CliError> {
+    if let Some(command) = ws_config.workspace_hook(HookPoint::PreRelease)
+        && !pkgs.is_empty()
+    {
+        workspace_hook(
+            ws_meta,
+            plan_path,
+            pkgs,
+            "pre-release",
+            command,
+            &[],
+            dry_run,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Points in a release where hooks run
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HookPoint {
+    PreRelease,
+    PostCommit,
+    PrePublish,
+    PostPublish,
+    PostTag,
+    PostPush,
+}
+
+impl HookPoint {
+    pub const ALL: [Self; 6] = [
+        Self::PreRelease,
+        Self::PostCommit,
+        Self::PrePublish,
+        Self::PostPublish,
+        Self::PostTag,
+        Self::PostPush,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::PreRelease => "pre-release",
+            Self::PostCommit => "post-commit",
+            Self::PrePublish => "pre-publish",
+            Self::PostPublish => "post-publish",
+            Self::PostTag => "post-tag",
+            Self::PostPush => "post-push",
+        }
+    }
+}
+
+/// Run the `point` hook of each of `pkgs`, and then the workspace's once
+///
+/// `envs` are the variables specific to `point`, for a package or, when `None`, the workspace.
+pub fn lifecycle_hook(
+    ws_meta: &cargo_metadata::Metadata,
+    plan_path: &Path,
+    ws_config: &Config,
+    pkgs: &[plan::PackageRelease],
+    point: HookPoint,
+    envs: impl Fn(Option<&plan::PackageRelease>) -> Vec<(&'static str, String)>,
+    dry_run: bool,
+) -> Result<(), CliError> {
+    for pkg in pkgs {
+        if let Some(command) = pkg.config.hook(point) {
+            let envs = envs(Some(pkg));
+            let envs = envs
+                .iter()
+                .map(|(key, value)| (*key, value.as_str()))
+                .collect::<Vec<_>>();
+            package_hook(
+                ws_meta,
+                plan_path,
+                pkg,
+                point.name(),
+                command,
+                &envs,
+                dry_run,
+            )?;
         }
     }
 
+    if !pkgs.is_empty()
+        && let Some(command) = ws_config.workspace_hook(point)
+    {
+        let envs = envs(None);
+        let envs = envs
+            .iter()
+            .map(|(key, value)| (*key, value.as_str()))
+            .collect::<Vec<_>>();
+        workspace_hook(
+            ws_meta,
+            plan_path,
+            pkgs,
+            point.name(),
+            command,
+            &envs,
+            dry_run,
+        )?;
+    }
+
     Ok(())
 }
+
+#[cfg(test)]
+#[cfg(unix)]
+mod test {
+    use super::*;
+
+    mod call_hook {
+        use super::*;
+
+        use crate::config::HookCommand;
+
+        fn hook(script: &str, timeout: Option<u64>, retries: u32) -> Command {
+            Command::Hook(HookCommand {
+                command: Box::new(Command::Args(vec![
+                    "sh".to_owned(),
+                    "-c".to_owned(),
+                    script.to_owned(),
+                ])),
+                timeout,
+                retries: Some(retries),
+                retry_delay: None,
+            })
+        }
+
+        fn run(command: &Command, cwd: &Path) -> bool {
+            let args = command.args().into_iter().map(|s| s.to_owned()).collect();
+            call_hook(command, args, Default::default(), cwd, "test hook").unwrap()
+        }
+
+        fn attempts(cwd: &Path) -> usize {
+            std::fs::read_to_string(cwd.join("attempts"))
+                .unwrap()
+                .lines()
+                .count()
+        }
+
+        #[test]
+        fn retries_until_exhausted() {
+            let temp = assert_fs::TempDir::new().unwrap();
+            let command = hook("echo x >> attempts; exit 1", None, 2);
+            assert!(!run(&command, temp.path()));
+            assert_eq!(attempts(temp.path()), 3);
+        }
+
+        #[test]
+        fn stops_retrying_on_success() {
+            let temp = assert_fs::TempDir::new().unwrap();
+            let command = hook(
+                "echo x >> attempts; test \"$(wc -l < attempts)\" -ge 2",
+                None,
+                5,
+            );
+            assert!(run(&command, temp.path()));
+            assert_eq!(attempts(temp.path()), 2);
+        }
+
+        #[test]
+        fn retries_timeout() {
+            let temp = assert_fs::TempDir::new().unwrap();
+            let command = hook("echo x >> attempts; sleep 10", Some(1), 1);
+            assert!(!run(&command, temp.path()));
+            assert_eq!(attempts(temp.path()), 2);
+        }
+    }
+    mod package_hook {
+        use super::*;
+
+        use crate::steps::BumpLevel;
+        use crate::steps::fixture::Workspace;
+
+        #[test]
+        fn release_plan() {
+            let ws = Workspace::new("", &[("foo", "0.1.0", &[])]);
+            let ws_meta = ws.metadata();
+            let pkgs = ws.plan(&ws_meta, BumpLevel::Minor);
+            let plan_file = plan::Report::new(&pkgs, &[], &plan::Checks::default())
+                .persist(&ws_meta)
+                .unwrap();
+            let seen = ws.root().join("seen.json");
+            let command = Command::Args(vec![
+                "sh".to_owned(),
+                "-c".to_owned(),
+                format!("cp \"$RELEASE_PLAN\" '{}'", seen.display()),
+            ]);
+
+            package_hook(
+                &ws_meta,
+                plan_file.path(),
+                &pkgs[0],
+                "pre-release",
+                &command,
+                &[],
+                false,
+            )
+            .unwrap();
+            let plan: serde_json::Value =
+                serde_json::from_str(&std::fs::read_to_string(&seen).unwrap()).unwrap();
+            assert_eq!(plan["packages"][0]["name"], "foo");
+            assert_eq!(plan["packages"][0]["planned-version"], "0.2.0");
+        }
+    }
+    mod workspace_pre_release_hook {
+        use super::*;
+
+        use crate::steps::BumpLevel;
+        use crate::steps::fixture::Workspace;
+
+        #[test]
+        fn runs_once_for_all_crates() {
+            let ws = Workspace::new(
+                r#"workspace-pre-release-hook = ["sh", "-c", 'echo run >> runs; cp "$RELEASE_PLAN" plan.json']"#,
+                &[
+                    ("foo", "0.1.0", &[]),
+                    ("bar", "0.1.0", &[]),
+                    ("baz", "0.1.0", &[]),
+                ],
+            );
+            let ws_meta = ws.metadata();
+            let ws_config =
+                crate::config::load_workspace_config(&ws.config_args(), &ws_meta).unwrap();
+            let pkgs = ws.plan(&ws_meta, BumpLevel::Minor);
+            let plan_file = plan::Report::new(&pkgs, &[], &plan::Checks::default())
+                .persist(&ws_meta)
+                .unwrap();
+
+            workspace_pre_release_hook(&ws_meta, plan_file.path(), &ws_config, &pkgs, false)
+                .unwrap();
+            let runs = std::fs::read_to_string(ws.root().join("runs")).unwrap();
+            assert_eq!(runs, "run\n");
+            let plan: serde_json::Value = serde_json::from_str(
+                &std::fs::read_to_string(ws.root().join("plan.json")).unwrap(),
+            )
+            .unwrap();
+            let mut released = plan["packages"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|p| p["selected"] == true)
+                .map(|p| p["name"].as_str().unwrap())
+                .collect::<Vec<_>>();
+            released.sort_unstable();
+            assert_eq!(released, ["bar", "baz", "foo"]);
+        }
+    }
+}
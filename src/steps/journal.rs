@@ -0,0 +1,278 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::error::CargoResult;
+use crate::steps::plan;
+
+/// Record of the actions completed for an in-progress release
+///
+/// This lets `cargo release --resume` pick up where a failed release left off.
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Journal {
+    #[serde(skip)]
+    path: PathBuf,
+    #[serde(skip)]
+    persist: bool,
+    packages: BTreeMap<String, PackageJournal>,
+}
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackageJournal {
+    pub initial_version: String,
+    pub planned_version: Option<String>,
+    pub planned_tag: Option<String>,
+    pub completed: Vec<Action>,
+}
+
+/// Release actions, in the order they are performed
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    Commit,
+    Publish,
+    Owner,
+    Tag,
+    PostRelease,
+    Push,
+}
+
+impl Action {
+    pub const ALL: [Self; 6] = [
+        Self::Commit,
+        Self::Publish,
+        Self::Owner,
+        Self::Tag,
+        Self::PostRelease,
+        Self::Push,
+    ];
+
+    /// What the release was doing while performing this action
+    pub fn doing(self) -> &'static str {
+        match self {
+            Self::Commit => "committing",
+            Self::Publish => "publishing",
+            Self::Owner => "updating owners",
+            Self::Tag => "tagging",
+            Self::PostRelease => "bumping to the next development version",
+            Self::Push => "pushing",
+        }
+    }
+}
+
+impl Journal {
+    pub fn path(ws_meta: &cargo_metadata::Metadata) -> PathBuf {
+        ws_meta
+            .target_directory
+            .as_std_path()
+            .join("release-state.json")
+    }
+
+    /// Start a journal for `pkgs`, only writing it to disk when `persist`
+    pub fn new(path: &Path, pkgs: &[plan::PackageRelease], persist: bool) -> CargoResult<Self> {
+        let packages = pkgs
+            .iter()
+            .map(|pkg| {
+                let journal = PackageJournal {
+                    initial_version: pkg.initial_version.full_version_string.clone(),
+                    planned_version: pkg
+                        .planned_version
+                        .as_ref()
+                        .map(|v| v.full_version_string.clone()),
+                    planned_tag: pkg.planned_tag.clone(),
+                    completed: Vec::new(),
+                };
+                (pkg.meta.name.to_string(), journal)
+            })
+            .collect();
+        let journal = Self {
+            path: path.to_owned(),
+            persist,
+            packages,
+        };
+        journal.save()?;
+        Ok(journal)
+    }
+
+    pub fn load(path: &Path, persist: bool) -> CargoResult<Self> {
+        let data = std::fs::read_to_string(path).map_err(|e| {
+            anyhow::format_err!(
+                "no release to resume, could not read {}: {e}",
+                path.display()
+            )
+        })?;
+        let mut journal: Self = serde_json::from_str(&data)
+            .map_err(|e| anyhow::format_err!("failed to parse {}: {e}", path.display()))?;
+        journal.path = path.to_owned();
+        journal.persist = persist;
+        Ok(journal)
+    }
+
+    pub fn package(&self, name: &str) -> Option<&PackageJournal> {
+        self.packages.get(name)
+    }
+
+    pub fn is_complete(&self, pkg: &plan::PackageRelease, action: Action) -> bool {
+        self.packages
+            .get(pkg.meta.name.as_str())
+            .map(|p| p.completed.contains(&action))
+            .unwrap_or(false)
+    }
+
+    /// `pkgs` that still need `action`
+    pub fn pending(
+        &self,
+        pkgs: &[plan::PackageRelease],
+        action: Action,
+    ) -> Vec<plan::PackageRelease> {
+        pkgs.iter()
+            .filter(|pkg| !self.is_complete(pkg, action))
+            .cloned()
+            .collect()
+    }
+
+    /// The first action still needed by any of `pkgs`
+    pub fn first_pending(&self, pkgs: &[plan::PackageRelease]) -> Option<Action> {
+        Action::ALL
+            .into_iter()
+            .find(|action| pkgs.iter().any(|pkg| !self.is_complete(pkg, *action)))
+    }
+
+    pub fn complete(&mut self, pkgs: &[plan::PackageRelease], action: Action) -> CargoResult<()> {
+        for pkg in pkgs {
+            if let Some(journal) = self.packages.get_mut(pkg.meta.name.as_str())
+                && !journal.completed.contains(&action)
+            {
+                journal.completed.push(action);
+            }
+        }
+        self.save()
+    }
+
+    fn save(&self) -> CargoResult<()> {
+        if !self.persist {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let data = serde_json::to_string_pretty(self)?;
+        std::fs::write(&self.path, data)?;
+        Ok(())
+    }
+
+    /// Drop the journal once the release is done
+    pub fn remove(self) -> CargoResult<()> {
+        if self.persist && self.path.exists() {
+            std::fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::steps::BumpLevel;
+    use crate::steps::fixture::Workspace;
+
+    fn workspace() -> Workspace {
+        Workspace::new(
+            "publish = false",
+            &[("foo", "0.1.0", &[]), ("bar", "0.2.0", &[])],
+        )
+    }
+
+    fn names(pkgs: &[plan::PackageRelease]) -> Vec<&str> {
+        let mut names = pkgs
+            .iter()
+            .map(|pkg| pkg.meta.name.as_str())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn round_trip() {
+        let ws = workspace();
+        let ws_meta = ws.metadata();
+        let pkgs = ws.plan(&ws_meta, BumpLevel::Minor);
+        let path = ws.root().join("target/release-state.json");
+
+        let mut journal = Journal::new(&path, &pkgs, true).unwrap();
+        journal.complete(&pkgs, Action::Commit).unwrap();
+        journal.complete(&pkgs[..1], Action::Publish).unwrap();
+
+        let loaded = Journal::load(&path, true).unwrap();
+        let foo = loaded.package("foo").unwrap();
+        assert_eq!(foo.initial_version, "0.1.0");
+        assert_eq!(foo.planned_version.as_deref(), Some("0.2.0"));
+        assert_eq!(foo.planned_tag.as_deref(), Some("foo-v0.2.0"));
+        let bar = loaded.package("bar").unwrap();
+        assert_eq!(bar.planned_version.as_deref(), Some("0.3.0"));
+        for pkg in &pkgs {
+            for action in Action::ALL {
+                assert_eq!(
+                    loaded.is_complete(pkg, action),
+                    journal.is_complete(pkg, action),
+                    "{} {action:?}",
+                    pkg.meta.name
+                );
+            }
+        }
+
+        loaded.remove().unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn not_persisted() {
+        let ws = workspace();
+        let ws_meta = ws.metadata();
+        let pkgs = ws.plan(&ws_meta, BumpLevel::Minor);
+        let path = ws.root().join("target/release-state.json");
+
+        let mut journal = Journal::new(&path, &pkgs, false).unwrap();
+        journal.complete(&pkgs, Action::Commit).unwrap();
+        assert!(!path.exists());
+        let err = Journal::load(&path, false).unwrap_err().to_string();
+        assert!(err.contains("no release to resume"), "{err}");
+    }
+
+    #[test]
+    fn resume_skips_completed() {
+        let ws = workspace();
+        let ws_meta = ws.metadata();
+        let pkgs = ws.plan(&ws_meta, BumpLevel::Minor);
+        let foo = pkgs
+            .iter()
+            .find(|pkg| pkg.meta.name.as_str() == "foo")
+            .unwrap();
+
+        let mut journal = Journal::new(&ws.root().join("state.json"), &pkgs, false).unwrap();
+        assert_eq!(journal.first_pending(&pkgs), Some(Action::Commit));
+        assert_eq!(
+            names(&journal.pending(&pkgs, Action::Commit)),
+            ["bar", "foo"]
+        );
+
+        journal.complete(&pkgs, Action::Commit).unwrap();
+        journal
+            .complete(std::slice::from_ref(foo), Action::Publish)
+            .unwrap();
+        assert!(journal.is_complete(foo, Action::Publish));
+        assert!(!journal.is_complete(foo, Action::Owner));
+        assert!(journal.pending(&pkgs, Action::Commit).is_empty());
+        assert_eq!(names(&journal.pending(&pkgs, Action::Publish)), ["bar"]);
+        assert_eq!(journal.first_pending(&pkgs), Some(Action::Publish));
+
+        for action in Action::ALL {
+            journal.complete(&pkgs, action).unwrap();
+        }
+        assert_eq!(journal.first_pending(&pkgs), None);
+    }
+}
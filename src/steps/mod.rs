@@ -3,13 +3,18 @@ use std::str::FromStr;
 pub mod changes;
 pub mod commit;
 pub mod config;
+#[cfg(test)]
+pub(crate) mod fixture;
 pub mod hook;
+pub mod journal;
 pub mod owner;
 pub mod plan;
+pub mod post_release;
 pub mod publish;
 pub mod push;
 pub mod release;
 pub mod replace;
+pub mod status;
 pub mod tag;
 pub mod version;
 
@@ -221,6 +226,166 @@ pub fn verify_monotonically_increasing(
     Ok(success)
 }
 
+pub fn verify_semver_compatible(
+    ws_meta: &cargo_metadata::Metadata,
+    pkgs: &[plan::PackageRelease],
+    index: &mut crate::ops::index::CratesIoIndex,
+    dry_run: bool,
+    level: log::Level,
+) -> Result<bool, crate::error::CliError> {
+    let mut success = true;
+
+    let mut incompatible_present = false;
+    for pkg in pkgs {
+        if !pkg.config.semver_check() {
+            continue;
+        }
+        let Some(version) = pkg.planned_version.as_ref() else {
+            continue;
+        };
+        let Some((baseline, changes)) = api_changes(ws_meta, pkg, index)? else {
+            continue;
+        };
+        if !crate::ops::api::allows(&baseline, &version.full_version, &changes) {
+            let crate_name = pkg.meta.name.as_str();
+            let breaking = changes.iter().any(|c| c.is_breaking());
+            let offending = changes
+                .iter()
+                .filter(|c| !breaking || c.is_breaking())
+                .map(|c| format!("\n  {c}"))
+                .collect::<String>();
+            let _ = crate::ops::shell::log(
+                level,
+                format!(
+                    "{} {} is too small of a bump from {} for its {} API changes:{}",
+                    crate_name,
+                    version.full_version,
+                    baseline,
+                    if breaking { "breaking" } else { "additive" },
+                    offending
+                ),
+            );
+            incompatible_present = true;
+        }
+    }
+    if incompatible_present && level == log::Level::Error {
+        success = false;
+        if !dry_run {
+            return Err(101.into());
+        }
+    }
+
+    Ok(success)
+}
+
+/// Public API changes of `pkg` since its prior tag or, failing that, its last published version
+fn api_changes(
+    ws_meta: &cargo_metadata::Metadata,
+    pkg: &plan::PackageRelease,
+    index: &mut crate::ops::index::CratesIoIndex,
+) -> Result<Option<(semver::Version, Vec<crate::ops::api::Change>)>, crate::error::CliError> {
+    let crate_name = pkg.meta.name.as_str();
+    let Some(lib) = pkg.meta.targets.iter().find(|t| t.is_lib()) else {
+        log::debug!("not checking the API of {crate_name}, it has no library");
+        return Ok(None);
+    };
+    let target_dir = ws_meta
+        .target_directory
+        .as_std_path()
+        .join("release-semver");
+
+    let baseline = if let Some(prior_tag) = pkg.prior_tag.as_deref() {
+        let _ = crate::ops::shell::status("Comparing", format!("{crate_name} API to {prior_tag}"));
+        let git_root = crate::ops::git::top_level(&pkg.package_root)?;
+        let worktree = target_dir.join("baseline-src");
+        if worktree.exists() {
+            // Left over from an interrupted check
+            if !crate::ops::git::worktree_remove(&git_root, &worktree)? {
+                let _ = crate::ops::shell::warn(format!(
+                    "failed to remove stale worktree {}",
+                    worktree.display()
+                ));
+            }
+        }
+        if !crate::ops::git::worktree_add(&git_root, &worktree, prior_tag)? {
+            return Err(anyhow::format_err!("failed to check out {prior_tag}").into());
+        }
+        let manifest_path = pkg
+            .manifest_path
+            .strip_prefix(&git_root)
+            .map(|p| worktree.join(p))
+            .map_err(|_| {
+                anyhow::format_err!(
+                    "{} is outside of {}",
+                    pkg.manifest_path.display(),
+                    git_root.display()
+                )
+            });
+        let json = manifest_path.and_then(|manifest_path| {
+            crate::ops::cargo::rustdoc_json(
+                &manifest_path,
+                crate_name,
+                &lib.name,
+                &target_dir.join("baseline"),
+            )
+        });
+        if !crate::ops::git::worktree_remove(&git_root, &worktree)? {
+            let _ = crate::ops::shell::warn(format!(
+                "failed to remove worktree {}; remove it with `git worktree remove --force`",
+                worktree.display()
+            ));
+        }
+        crate::ops::api::Api::from_rustdoc_json(&json?)?
+    } else if let Some(krate) =
+        index.krate(pkg.config.registry(), crate_name, pkg.config.certs_source())?
+    {
+        let planned = &pkg.planned_version.as_ref().unwrap_or(&pkg.initial_version);
+        let Some(published) = krate
+            .versions
+            .iter()
+            .filter(|v| !v.yanked)
+            .filter_map(|v| semver::Version::parse(&v.version).ok())
+            .filter(|v| *v < planned.full_version)
+            .max()
+        else {
+            log::debug!("not checking the API of {crate_name}, nothing earlier was published");
+            return Ok(None);
+        };
+        let _ = crate::ops::shell::status(
+            "Comparing",
+            format!(
+                "{crate_name} API to {published} on {}",
+                pkg.config.registry().unwrap_or("crates.io")
+            ),
+        );
+        let json = crate::ops::cargo::registry_rustdoc_json(
+            crate_name,
+            &published,
+            pkg.config.registry(),
+            &lib.name,
+            &target_dir.join("baseline"),
+        )?;
+        crate::ops::api::Api::from_rustdoc_json(&json)?
+    } else {
+        log::debug!("not checking the API of {crate_name}, there is no prior release");
+        return Ok(None);
+    };
+
+    let json = crate::ops::cargo::rustdoc_json(
+        &pkg.manifest_path,
+        crate_name,
+        &lib.name,
+        &target_dir.join("current"),
+    )?;
+    let current = crate::ops::api::Api::from_rustdoc_json(&json)?;
+
+    let baseline_version = baseline
+        .version()
+        .unwrap_or(&pkg.initial_version.full_version)
+        .clone();
+    Ok(Some((baseline_version, current.diff(&baseline))))
+}
+
 pub fn verify_rate_limit(
     pkgs: &[plan::PackageRelease],
     index: &mut crate::ops::index::CratesIoIndex,
@@ -233,13 +398,13 @@ pub fn verify_rate_limit(
     // "It's not particularly secret, we just don't publish it other than in the code because
     // it's subject to change. The responses from the rate limited requests on when to try
     // again contain the most accurate information."
-    let mut new = 0;
-    let mut existing = 0;
+    let mut new: usize = 0;
+    let mut existing: usize = 0;
     for pkg in pkgs {
         // Note: these rate limits are only known for default registry
         if pkg.config.registry().is_none() && pkg.config.publish() {
             let crate_name = pkg.meta.name.as_str();
-            if index.has_krate(None, crate_name, pkg.config.certs_source())? {
+            if crate::ops::cargo::crate_exists(index, None, crate_name, pkg.config.certs_source()) {
                 existing += 1;
             } else {
                 new += 1;
@@ -247,6 +412,24 @@ pub fn verify_rate_limit(
         }
     }
 
+    if rate_limit.schedule() {
+        let new_wait = publish::NEW_PACKAGE_REFILL
+            * u32::try_from(new.saturating_sub(rate_limit.new_packages())).unwrap_or(u32::MAX);
+        let existing_wait = publish::EXISTING_PACKAGE_REFILL
+            * u32::try_from(existing.saturating_sub(rate_limit.existing_packages()))
+                .unwrap_or(u32::MAX);
+        let wait = new_wait.max(existing_wait);
+        if !wait.is_zero() {
+            let _ = crate::ops::shell::note(format!(
+                "publishing {} new and {} existing crates will be spread over at least {} minutes to stay within the rate limit",
+                new,
+                existing,
+                wait.as_secs() / 60
+            ));
+        }
+        return Ok(success);
+    }
+
     if rate_limit.new_packages() < new {
         // "The rate limit for creating new crates is 1 crate every 10 minutes, with a burst of 5 crates."
         success = false;
@@ -390,6 +573,147 @@ pub fn warn_changed(
     Ok(())
 }
 
+/// Select packages by [release group][crate::config::Group]
+#[derive(Clone, Default, Debug, clap::Args)]
+pub struct GroupArgs {
+    /// Process the members of the named release group
+    #[arg(long, value_name = "NAME")]
+    pub group: Vec<String>,
+}
+
+impl GroupArgs {
+    /// Add the members of each `--group` to the `--package` selection
+    pub fn select(
+        &self,
+        workspace: &clap_cargo::Workspace,
+        ws_config: &crate::config::Config,
+        ws_meta: &cargo_metadata::Metadata,
+    ) -> CargoResult<clap_cargo::Workspace> {
+        let mut workspace = workspace.clone();
+        for group_name in &self.group {
+            let Some(group) = ws_config.groups().get(group_name) else {
+                anyhow::bail!("unknown group `{group_name}`");
+            };
+            let matcher = group.matcher()?;
+            for pkg in ws_meta.workspace_packages() {
+                if matcher.is_match(pkg.name.as_str()) && !workspace.package.contains(&pkg.name) {
+                    workspace.package.push(pkg.name.to_string());
+                }
+            }
+        }
+        Ok(workspace)
+    }
+}
+
+/// Packages with files changed since their prior tag, along with the dependents whose requirement
+/// on them must move
+///
+/// `pkgs` must be sorted with dependencies before their dependents.
+pub fn find_changed<'p>(
+    ws_meta: &cargo_metadata::Metadata,
+    pkgs: impl IntoIterator<Item = &'p plan::PackageRelease>,
+) -> std::collections::HashSet<cargo_metadata::PackageId> {
+    let mut changed_pkgs = std::collections::HashSet::new();
+    for pkg in pkgs {
+        let crate_name = pkg.meta.name.as_str();
+        if !pkg.config.release() {
+            continue;
+        }
+        if let Some(prior_tag_name) = &pkg.prior_tag {
+            match version::changed_since(ws_meta, pkg, prior_tag_name) {
+                Some(changed) if !changed.is_empty() => {
+                    log::debug!(
+                        "Files changed in {crate_name} since {prior_tag_name}: {changed:#?}"
+                    );
+                    changed_pkgs.insert(pkg.meta.id.clone());
+                }
+                Some(_) => {}
+                None => {
+                    log::debug!(
+                        "cannot detect changes for {crate_name} because tag {prior_tag_name} is missing. Try setting `--prev-tag-name <TAG>`."
+                    );
+                }
+            }
+        } else {
+            log::debug!("no tag found for {crate_name}, assuming it is unreleased");
+            changed_pkgs.insert(pkg.meta.id.clone());
+        }
+
+        if !changed_pkgs.contains(&pkg.meta.id) {
+            continue;
+        }
+        let Some(version) = pkg.planned_version.as_ref() else {
+            continue;
+        };
+        for dependent in &pkg.dependents {
+            if !dependent.is_dev_only(crate_name) && pkg.moves_requirement(dependent, version) {
+                log::debug!(
+                    "{} requirement on {crate_name} moves to {}",
+                    dependent.pkg.name,
+                    version.full_version_string
+                );
+                changed_pkgs.insert(dependent.pkg.id.clone());
+            }
+        }
+    }
+    changed_pkgs
+}
+
+/// Packages left out of the selection
+///
+/// `--changed` adds the packages changed since their last release to `--package` and is only
+/// narrowed by `--exclude`.  `select_all` overrides the selection for flags like `--unpublished`.
+pub fn deselected_packages<'m>(
+    ws_meta: &'m cargo_metadata::Metadata,
+    workspace: &clap_cargo::Workspace,
+    pkgs: &indexmap::IndexMap<cargo_metadata::PackageId, plan::PackageRelease>,
+    changed: bool,
+    select_all: bool,
+) -> Vec<&'m cargo_metadata::Package> {
+    let changed_pkgs = if changed {
+        find_changed(
+            ws_meta,
+            pkgs.values()
+                .filter(|p| !workspace.exclude.contains(&p.meta.name)),
+        )
+    } else {
+        Default::default()
+    };
+    let (_selected_pkgs, excluded_pkgs): (Vec<_>, Vec<_>) =
+        if select_all || (changed && workspace.package.is_empty()) {
+            ws_meta.packages.iter().partition(|_| false)
+        } else {
+            workspace.partition_packages(ws_meta)
+        };
+    excluded_pkgs
+        .into_iter()
+        .filter(|excluded_pkg| {
+            let enabled = changed_pkgs.contains(&excluded_pkg.id)
+                && !workspace.exclude.contains(&excluded_pkg.name);
+            if enabled {
+                log::debug!(
+                    "enabled {}, changed since its last release",
+                    excluded_pkg.name
+                );
+            }
+            !enabled
+        })
+        .collect()
+}
+
+/// Check `level_or_version` applies to every package being released, before doing anything
+pub fn validate_target(
+    level_or_version: &TargetVersion,
+    pkgs: &indexmap::IndexMap<cargo_metadata::PackageId, plan::PackageRelease>,
+) -> CargoResult<()> {
+    if let TargetVersion::Relative(level) = level_or_version {
+        for pkg in pkgs.values().filter(|pkg| pkg.config.release()) {
+            level.validate(pkg.config.pre_release_channels())?;
+        }
+    }
+    Ok(())
+}
+
 pub fn find_shared_versions(
     pkgs: &[plan::PackageRelease],
 ) -> Result<Option<plan::Version>, crate::error::CliError> {
@@ -504,10 +828,21 @@ pub fn finish(failed: bool, dry_run: bool) -> Result<(), crate::error::CliError>
     }
 }
 
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable status messages
+    #[default]
+    Text,
+    /// Machine-readable JSON on stdout
+    Json,
+}
+
 #[derive(Clone, Debug)]
 pub enum TargetVersion {
     Relative(BumpLevel),
     Absolute(semver::Version),
+    /// Derive the [`BumpLevel`] from the commits since the prior tag
+    Auto,
 }
 
 impl TargetVersion {
@@ -515,11 +850,12 @@ impl TargetVersion {
         &self,
         current: &semver::Version,
         metadata: Option<&str>,
+        pre_release_channels: &[String],
     ) -> CargoResult<Option<plan::Version>> {
         match self {
             Self::Relative(bump_level) => {
                 let mut potential_version = current.to_owned();
-                bump_level.bump_version(&mut potential_version, metadata)?;
+                bump_level.bump_version(&mut potential_version, metadata, pre_release_channels)?;
                 if potential_version != *current {
                     let full_version = potential_version;
                     let version = plan::Version::from(full_version);
@@ -544,6 +880,9 @@ impl TargetVersion {
                     Ok(None)
                 }
             }
+            Self::Auto => {
+                anyhow::bail!("`auto` must be resolved to a bump level per package")
+            }
         }
     }
 }
@@ -563,6 +902,9 @@ impl std::fmt::Display for TargetVersion {
             Self::Absolute(version) => {
                 write!(f, "{version}")
             }
+            Self::Auto => {
+                write!(f, "auto")
+            }
         }
     }
 }
@@ -571,7 +913,9 @@ impl FromStr for TargetVersion {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Ok(bump_level) = BumpLevel::from_str(s) {
+        if s == "auto" {
+            Ok(Self::Auto)
+        } else if let Ok(bump_level) = BumpLevel::from_str(s) {
             Ok(Self::Relative(bump_level))
         } else {
             Ok(Self::Absolute(
@@ -608,18 +952,18 @@ impl clap::builder::TypedValueParser for TargetVersionParser {
     fn possible_values(
         &self,
     ) -> Option<Box<dyn Iterator<Item = clap::builder::PossibleValue> + '_>> {
-        let inner_parser = clap::builder::EnumValueParser::<BumpLevel>::new();
-        inner_parser.possible_values().map(|ps| {
-            let ps = ps.collect::<Vec<_>>();
-            let ps: Box<dyn Iterator<Item = clap::builder::PossibleValue> + '_> =
-                Box::new(ps.into_iter());
-            ps
-        })
+        let mut ps = BumpLevel::possible_values().collect::<Vec<_>>();
+        ps.push(
+            clap::builder::PossibleValue::new("auto")
+                .help("Bump according to the conventional commits since the last release"),
+        );
+        let ps: Box<dyn Iterator<Item = clap::builder::PossibleValue> + '_> =
+            Box::new(ps.into_iter());
+        Some(ps)
     }
 }
 
-#[derive(Debug, Clone, Copy, clap::ValueEnum)]
-#[value(rename_all = "kebab-case")]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum BumpLevel {
     /// Increase the major version (x.0.0)
     Major,
@@ -629,45 +973,149 @@ pub enum BumpLevel {
     Patch,
     /// Remove the pre-version (x.y.z)
     Release,
-    /// Increase the rc pre-version (x.y.z-rc.M)
-    Rc,
-    /// Increase the beta pre-version (x.y.z-beta.M)
-    Beta,
-    /// Increase the alpha pre-version (x.y.z-alpha.M)
-    Alpha,
+    /// Increase the pre-version for one of the `pre-release-channels` (x.y.z-<channel>.M)
+    Pre(String),
+    /// Increase the major version and start a pre-release (x.0.0-<channel>.1)
+    ///
+    /// Without a channel, the first of the `pre-release-channels` is used.
+    PreMajor(Option<String>),
+    /// Increase the minor version and start a pre-release (x.y.0-<channel>.1)
+    PreMinor(Option<String>),
+    /// Increase the patch version and start a pre-release (x.y.z-<channel>.1)
+    PrePatch(Option<String>),
+    /// Set the version from today's date per `calver-format` (YYYY.MM.MICRO)
+    Calver,
+}
+
+impl BumpLevel {
+    /// Levels for `--help`, leaving out the `pre-release-channels` as they are configurable
+    fn possible_values() -> impl Iterator<Item = clap::builder::PossibleValue> {
+        Self::NAMES
+            .into_iter()
+            .map(|name| clap::builder::PossibleValue::new(name).help(Self::help(name)))
+    }
+
+    fn help(name: &str) -> &'static str {
+        match name {
+            "major" => "Increase the major version (x.0.0)",
+            "minor" => "Increase the minor version (x.y.0)",
+            "patch" => "Increase the patch version (x.y.z)",
+            "release" => "Remove the pre-version (x.y.z)",
+            "premajor" => {
+                "Increase the major version and start a pre-release, like `premajor:beta` (x.0.0-beta.1)"
+            }
+            "preminor" => {
+                "Increase the minor version and start a pre-release, like `preminor:beta` (x.y.0-beta.1)"
+            }
+            "prepatch" => {
+                "Increase the patch version and start a pre-release, like `prepatch:beta` (x.y.z-beta.1)"
+            }
+            "calver" => "Set the version from today's date per `calver-format` (YYYY.MM.MICRO)",
+            _ => "",
+        }
+    }
 }
 
 impl std::fmt::Display for BumpLevel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        use clap::ValueEnum;
+        match self {
+            Self::Major => "major".fmt(f),
+            Self::Minor => "minor".fmt(f),
+            Self::Patch => "patch".fmt(f),
+            Self::Release => "release".fmt(f),
+            Self::Pre(channel) => channel.fmt(f),
+            Self::PreMajor(channel) => fmt_pre_level(f, "premajor", channel.as_deref()),
+            Self::PreMinor(channel) => fmt_pre_level(f, "preminor", channel.as_deref()),
+            Self::PrePatch(channel) => fmt_pre_level(f, "prepatch", channel.as_deref()),
+            Self::Calver => "calver".fmt(f),
+        }
+    }
+}
 
-        self.to_possible_value()
-            .expect("no values are skipped")
-            .get_name()
-            .fmt(f)
+fn fmt_pre_level(
+    f: &mut std::fmt::Formatter<'_>,
+    level: &str,
+    channel: Option<&str>,
+) -> std::fmt::Result {
+    if let Some(channel) = channel {
+        write!(f, "{level}:{channel}")
+    } else {
+        f.write_str(level)
     }
 }
 
+fn is_pre_release_identifier(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        && !s.chars().all(|c| c.is_ascii_digit())
+}
+
 impl FromStr for BumpLevel {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        use clap::ValueEnum;
-
-        for variant in Self::value_variants() {
-            if variant.to_possible_value().unwrap().matches(s, false) {
-                return Ok(*variant);
+        let (level, channel) = match s.split_once(':') {
+            Some((level, channel)) if is_pre_release_identifier(channel) => {
+                (level, Some(channel.to_owned()))
             }
+            Some(_) => return Err(format!("Invalid variant: {s}")),
+            None => (s, None),
+        };
+        match level {
+            "premajor" => return Ok(Self::PreMajor(channel)),
+            "preminor" => return Ok(Self::PreMinor(channel)),
+            "prepatch" => return Ok(Self::PrePatch(channel)),
+            _ if channel.is_some() => return Err(format!("Invalid variant: {s}")),
+            _ => {}
+        }
+
+        match s {
+            "major" => Ok(Self::Major),
+            "minor" => Ok(Self::Minor),
+            "patch" => Ok(Self::Patch),
+            "release" => Ok(Self::Release),
+            "calver" => Ok(Self::Calver),
+            // Any other identifier is validated against `pre-release-channels` when bumping
+            _ if is_pre_release_identifier(s) => Ok(Self::Pre(s.to_owned())),
+            _ => Err(format!("Invalid variant: {s}")),
         }
-        Err(format!("Invalid variant: {s}"))
     }
 }
 
 impl BumpLevel {
+    /// Levels other than pre-release channels, see [`TargetVersion`] for `auto`
+    const NAMES: [&'static str; 8] = [
+        "major", "minor", "patch", "release", "premajor", "preminor", "prepatch", "calver",
+    ];
+
+    /// Check the level's channel, if any, is one of `pre_release_channels`
+    pub fn validate(&self, pre_release_channels: &[String]) -> CargoResult<()> {
+        let channel = match self {
+            Self::Pre(channel) => channel,
+            Self::PreMajor(Some(channel))
+            | Self::PreMinor(Some(channel))
+            | Self::PrePatch(Some(channel)) => channel,
+            _ => return Ok(()),
+        };
+        if !pre_release_channels.iter().any(|c| c == channel) {
+            anyhow::bail!(
+                "unknown bump level `{self}`, expected one of {}, `auto`, or a `pre-release-channels` entry: {}",
+                Self::NAMES.map(|name| format!("`{name}`")).join(", "),
+                pre_release_channels
+                    .iter()
+                    .map(|c| format!("`{c}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        Ok(())
+    }
+
     pub fn bump_version(
-        self,
+        &self,
         version: &mut semver::Version,
         metadata: Option<&str>,
+        pre_release_channels: &[String],
     ) -> CargoResult<()> {
         match self {
             Self::Major => {
@@ -688,14 +1136,23 @@ impl BumpLevel {
                     version.pre = semver::Prerelease::EMPTY;
                 }
             }
-            Self::Rc => {
-                version.increment_rc()?;
+            Self::Pre(channel) => {
+                version.increment_prerelease(channel, pre_release_channels)?;
             }
-            Self::Beta => {
-                version.increment_beta()?;
+            Self::PreMajor(channel) => {
+                version.increment_major();
+                start_prerelease(version, channel.as_deref(), pre_release_channels)?;
+            }
+            Self::PreMinor(channel) => {
+                version.increment_minor();
+                start_prerelease(version, channel.as_deref(), pre_release_channels)?;
+            }
+            Self::PrePatch(channel) => {
+                version.increment_patch();
+                start_prerelease(version, channel.as_deref(), pre_release_channels)?;
             }
-            Self::Alpha => {
-                version.increment_alpha()?;
+            Self::Calver => {
+                anyhow::bail!("`calver` must be resolved with the package's `calver-format`")
             }
         };
 
@@ -706,3 +1163,352 @@ impl BumpLevel {
         Ok(())
     }
 }
+
+/// Enter the first pre-release of `channel`, defaulting to the least stable channel
+fn start_prerelease(
+    version: &mut semver::Version,
+    channel: Option<&str>,
+    pre_release_channels: &[String],
+) -> CargoResult<()> {
+    let channel = match channel {
+        Some(channel) => {
+            if !pre_release_channels.iter().any(|c| c == channel) {
+                anyhow::bail!(
+                    "`{channel}` is not a pre-release channel, expected one of {}",
+                    pre_release_channels.join(", ")
+                );
+            }
+            channel
+        }
+        None => pre_release_channels
+            .first()
+            .ok_or_else(|| anyhow::format_err!("no `pre-release-channels` are configured"))?,
+    };
+    version.pre = semver::Prerelease::new(&format!("{channel}.1"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod validate {
+        use super::*;
+
+        fn channels() -> Vec<String> {
+            crate::ops::version::default_channels()
+        }
+
+        #[test]
+        fn known_levels() {
+            for level in [
+                "major",
+                "release",
+                "calver",
+                "beta",
+                "premajor",
+                "prepatch:rc",
+            ] {
+                let level = BumpLevel::from_str(level).unwrap();
+                level.validate(&channels()).unwrap();
+            }
+        }
+
+        #[test]
+        fn unknown_channel() {
+            let err = BumpLevel::from_str("majr")
+                .unwrap()
+                .validate(&channels())
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("`majr`"), "{err}");
+            assert!(err.contains("`major`"), "{err}");
+            assert!(err.contains("`auto`"), "{err}");
+            assert!(err.contains("`alpha`, `beta`, `rc`"), "{err}");
+
+            let err = BumpLevel::from_str("preminor:nightly")
+                .unwrap()
+                .validate(&channels())
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("`preminor:nightly`"), "{err}");
+        }
+
+        #[test]
+        fn configured_channel() {
+            let channels = vec!["nightly".to_owned()];
+            BumpLevel::from_str("nightly")
+                .unwrap()
+                .validate(&channels)
+                .unwrap();
+            assert!(
+                BumpLevel::from_str("beta")
+                    .unwrap()
+                    .validate(&channels)
+                    .is_err()
+            );
+        }
+    }
+
+    mod from_str {
+        use super::*;
+
+        #[test]
+        fn pre_levels() {
+            assert_eq!(
+                BumpLevel::from_str("premajor"),
+                Ok(BumpLevel::PreMajor(None))
+            );
+            assert_eq!(
+                BumpLevel::from_str("preminor:beta"),
+                Ok(BumpLevel::PreMinor(Some("beta".to_owned())))
+            );
+            assert_eq!(
+                BumpLevel::from_str("prepatch:rc"),
+                Ok(BumpLevel::PrePatch(Some("rc".to_owned())))
+            );
+            assert_eq!(
+                BumpLevel::from_str("beta"),
+                Ok(BumpLevel::Pre("beta".to_owned()))
+            );
+        }
+
+        #[test]
+        fn channel_requires_pre_level() {
+            assert!(BumpLevel::from_str("major:beta").is_err());
+            assert!(BumpLevel::from_str("release:rc").is_err());
+            assert!(BumpLevel::from_str("beta:rc").is_err());
+        }
+
+        #[test]
+        fn invalid_channel() {
+            assert!(BumpLevel::from_str("premajor:").is_err());
+            assert!(BumpLevel::from_str("premajor:be ta").is_err());
+        }
+
+        #[test]
+        fn round_trip() {
+            for level in [
+                "premajor",
+                "preminor:beta",
+                "prepatch:rc",
+                "alpha",
+                "calver",
+            ] {
+                assert_eq!(BumpLevel::from_str(level).unwrap().to_string(), level);
+            }
+        }
+    }
+
+    mod bump_version {
+        use super::*;
+
+        fn bump(version: &str, level: &str) -> CargoResult<String> {
+            let mut version = semver::Version::parse(version).unwrap();
+            BumpLevel::from_str(level).unwrap().bump_version(
+                &mut version,
+                None,
+                &crate::ops::version::default_channels(),
+            )?;
+            Ok(version.to_string())
+        }
+
+        #[test]
+        fn default_channel() {
+            assert_eq!(bump("1.2.3", "premajor").unwrap(), "2.0.0-alpha.1");
+            assert_eq!(bump("1.2.3", "preminor").unwrap(), "1.3.0-alpha.1");
+            assert_eq!(bump("1.2.3", "prepatch").unwrap(), "1.2.4-alpha.1");
+        }
+
+        #[test]
+        fn named_channel() {
+            assert_eq!(bump("1.2.3", "premajor:rc").unwrap(), "2.0.0-rc.1");
+            assert_eq!(bump("1.2.3", "preminor:beta").unwrap(), "1.3.0-beta.1");
+            assert_eq!(bump("1.2.3", "prepatch:beta").unwrap(), "1.2.4-beta.1");
+        }
+
+        #[test]
+        fn from_pre_release() {
+            assert_eq!(
+                bump("2.0.0-alpha.3", "premajor:beta").unwrap(),
+                "3.0.0-beta.1"
+            );
+            assert_eq!(bump("1.3.0-beta.2", "prepatch").unwrap(), "1.3.1-alpha.1");
+        }
+
+        #[test]
+        fn unknown_channel() {
+            let err = bump("1.2.3", "premajor:nightly").unwrap_err().to_string();
+            assert!(
+                err.contains("`nightly` is not a pre-release channel"),
+                "{err}"
+            );
+        }
+    }
+
+    mod find_changed {
+        use super::*;
+
+        use crate::steps::fixture::{Workspace, id};
+
+        fn workspace() -> Workspace {
+            let ws = Workspace::new(
+                "",
+                &[
+                    ("foo", "0.1.0", &[]),
+                    ("bar", "0.1.0", &[("foo", "0.1")]),
+                    ("baz", "1.0.0", &[]),
+                ],
+            );
+            ws.tag("foo-v0.1.0");
+            ws.tag("bar-v0.1.0");
+            ws.tag("baz-v1.0.0");
+            ws
+        }
+
+        fn names(
+            pkgs: &indexmap::IndexMap<cargo_metadata::PackageId, plan::PackageRelease>,
+            changed: &std::collections::HashSet<cargo_metadata::PackageId>,
+        ) -> Vec<String> {
+            pkgs.values()
+                .filter(|p| changed.contains(&p.meta.id))
+                .map(|p| p.meta.name.to_string())
+                .collect()
+        }
+
+        #[test]
+        fn unchanged() {
+            let ws = workspace();
+            let ws_meta = ws.metadata();
+            let pkgs = ws.load(&ws_meta);
+
+            let changed = find_changed(&ws_meta, pkgs.values());
+            assert!(changed.is_empty(), "{changed:?}");
+        }
+
+        #[test]
+        fn changed() {
+            let ws = workspace();
+            ws.write("baz/src/lib.rs", "pub fn baz() {}\n");
+            ws.commit("feat: Add baz");
+            let ws_meta = ws.metadata();
+            let pkgs = ws.load(&ws_meta);
+
+            let changed = find_changed(&ws_meta, pkgs.values());
+            assert_eq!(names(&pkgs, &changed), ["baz"]);
+        }
+
+        #[test]
+        fn missing_prior_tag() {
+            let ws = workspace();
+            ws.git(&["tag", "-d", "baz-v1.0.0"]);
+            let ws_meta = ws.metadata();
+            let pkgs = ws.load(&ws_meta);
+
+            let changed = find_changed(&ws_meta, pkgs.values());
+            assert_eq!(names(&pkgs, &changed), ["baz"]);
+        }
+
+        #[test]
+        fn dependent_requirement_moves() {
+            let ws = workspace();
+            ws.write("foo/src/lib.rs", "pub fn foo() {}\n");
+            ws.commit("feat: Add foo");
+            let ws_meta = ws.metadata();
+            let mut pkgs = ws.load(&ws_meta);
+            let foo = id(&pkgs, "foo").clone();
+
+            // Unplanned, only `foo` itself changed
+            let changed = find_changed(&ws_meta, pkgs.values());
+            assert_eq!(names(&pkgs, &changed), ["foo"]);
+
+            // A compatible version leaves `bar`'s requirement alone
+            pkgs[&foo]
+                .bump(&ws_meta, &TargetVersion::Relative(BumpLevel::Patch), None)
+                .unwrap();
+            let changed = find_changed(&ws_meta, pkgs.values());
+            assert_eq!(names(&pkgs, &changed), ["foo"]);
+
+            pkgs[&foo]
+                .bump(&ws_meta, &TargetVersion::Relative(BumpLevel::Minor), None)
+                .unwrap();
+            let changed = find_changed(&ws_meta, pkgs.values());
+            assert_eq!(names(&pkgs, &changed), ["foo", "bar"]);
+        }
+
+        #[test]
+        fn exclude_narrows() {
+            let ws = workspace();
+            ws.write("foo/src/lib.rs", "pub fn foo() {}\n");
+            ws.write("baz/src/lib.rs", "pub fn baz() {}\n");
+            ws.commit("feat: Add foo and baz");
+            let ws_meta = ws.metadata();
+            let mut pkgs = ws.load(&ws_meta);
+            for pkg in pkgs.values_mut() {
+                pkg.bump(&ws_meta, &TargetVersion::Relative(BumpLevel::Minor), None)
+                    .unwrap();
+            }
+            let mut workspace = clap_cargo::Workspace::default();
+            workspace.exclude = vec!["foo".to_owned()];
+
+            let deselected = deselected_packages(&ws_meta, &workspace, &pkgs, true, false);
+            let mut deselected = deselected
+                .iter()
+                .map(|p| p.name.to_string())
+                .collect::<Vec<_>>();
+            deselected.sort();
+            // `bar` is not pulled in by the excluded `foo`
+            assert_eq!(deselected, ["bar", "foo"]);
+        }
+    }
+
+    mod verify_rate_limit {
+        use super::*;
+
+        use crate::steps::fixture::Workspace;
+
+        /// Index for crates.io that can't be reached
+        fn unreachable_index() -> crate::ops::index::CratesIoIndex {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("sparse+http://{}/", listener.local_addr().unwrap());
+            drop(listener);
+            let mut index = crate::ops::index::CratesIoIndex::new();
+            index.insert(
+                None,
+                crate::ops::index::RemoteIndex::from_url(
+                    None,
+                    &url,
+                    crate::config::CertsSource::Webpki,
+                )
+                .unwrap(),
+            );
+            index
+        }
+
+        #[test]
+        fn unreachable_index_counts_as_new() {
+            let ws = Workspace::new("", &[("foo", "0.1.0", &[]), ("bar", "0.1.0", &[])]);
+            let ws_meta = ws.metadata();
+            let pkgs: Vec<_> = ws.load(&ws_meta).into_values().collect();
+            let mut index = unreachable_index();
+
+            let rate_limit = crate::config::RateLimit {
+                new_packages: Some(2),
+                ..Default::default()
+            };
+            assert!(
+                verify_rate_limit(&pkgs, &mut index, &rate_limit, false, log::Level::Error)
+                    .unwrap()
+            );
+
+            let rate_limit = crate::config::RateLimit {
+                new_packages: Some(1),
+                ..Default::default()
+            };
+            assert!(
+                !verify_rate_limit(&pkgs, &mut index, &rate_limit, false, log::Level::Warn)
+                    .unwrap()
+            );
+        }
+    }
+}
@@ -11,6 +11,9 @@ pub struct OwnerStep {
     #[command(flatten)]
     workspace: clap_cargo::Workspace,
 
+    #[command(flatten)]
+    group: super::GroupArgs,
+
     /// Custom config file
     #[arg(short, long = "config", value_name = "PATH")]
     custom_config: Option<std::path::PathBuf>,
@@ -56,9 +59,10 @@ impl OwnerStep {
             .exec()?;
         let config = self.to_config();
         let ws_config = crate::config::load_workspace_config(&config, &ws_meta)?;
+        let workspace = self.group.select(&self.workspace, &ws_config, &ws_meta)?;
         let mut pkgs = plan::load(&config, &ws_meta)?;
 
-        let (_selected_pkgs, excluded_pkgs) = self.workspace.partition_packages(&ws_meta);
+        let (_selected_pkgs, excluded_pkgs) = workspace.partition_packages(&ws_meta);
         for excluded_pkg in excluded_pkgs {
             let Some(pkg) = pkgs.get_mut(&excluded_pkg.id) else {
                 // Either not in workspace or marked as `release = false`.
@@ -7,6 +7,7 @@ use crate::ops::cargo;
 use crate::ops::git;
 use crate::ops::replace::Template;
 use crate::ops::version::VersionExt as _;
+use crate::steps::BumpLevel;
 
 pub fn load(
     args: &config::ConfigArgs,
@@ -14,17 +15,73 @@ pub fn load(
 ) -> CargoResult<indexmap::IndexMap<cargo_metadata::PackageId, PackageRelease>> {
     let root = git::top_level(ws_meta.workspace_root.as_std_path())?;
 
+    let ws_config = config::load_workspace_config(args, ws_meta)?;
     let member_ids = cargo::sort_workspace(ws_meta);
+    let groups = ws_config.group_members(member_ids.iter().map(|p| ws_meta[p].name.as_str()))?;
     member_ids
         .iter()
-        .map(|p| PackageRelease::load(args, &root, ws_meta, &ws_meta[p]))
+        .map(|p| {
+            let pkg_meta = &ws_meta[p];
+            let group = groups
+                .get(pkg_meta.name.as_str())
+                .map(|name| (*name, &ws_config.groups()[*name]));
+            PackageRelease::load(args, &root, ws_meta, pkg_meta, group)
+        })
         .map(|p| p.map(|p| (p.meta.id.clone(), p)))
         .collect()
 }
 
 pub fn plan(
+    pkgs: indexmap::IndexMap<cargo_metadata::PackageId, PackageRelease>,
+) -> CargoResult<indexmap::IndexMap<cargo_metadata::PackageId, PackageRelease>> {
+    plan_with_dependents(pkgs, &Default::default())
+}
+
+/// [`plan`], pulling `deselected` packages back in when dependents need releasing
+///
+/// `deselected` are packages that may be pulled back into the release because they were only left
+/// out by package selection.
+pub fn plan_with_dependents(
     mut pkgs: indexmap::IndexMap<cargo_metadata::PackageId, PackageRelease>,
+    deselected: &std::collections::HashSet<cargo_metadata::PackageId>,
 ) -> CargoResult<indexmap::IndexMap<cargo_metadata::PackageId, PackageRelease>> {
+    // Dependents of a shared version can only be found once it is resolved, while including a
+    // dependent can raise the version it shares
+    loop {
+        share_versions(&mut pkgs);
+        if !cascade_dependents(&mut pkgs, deselected)? {
+            break;
+        }
+    }
+
+    for pkg in pkgs.values_mut() {
+        pkg.plan()?;
+    }
+
+    let released = || pkgs.values().filter(|pkg| pkg.config.release());
+    if released().any(|pkg| pkg.config.workspace_tag())
+        && released().any(|pkg| !pkg.config.workspace_tag())
+    {
+        anyhow::bail!(
+            "inconsistent `workspace-tag` setting, it must be set for the whole workspace"
+        );
+    }
+    let workspace_tags = released()
+        .filter(|pkg| pkg.config.workspace_tag())
+        .filter_map(|pkg| pkg.planned_tag.as_deref())
+        .collect::<std::collections::BTreeSet<_>>();
+    if 1 < workspace_tags.len() {
+        anyhow::bail!(
+            "`workspace-tag` requires released packages to share a tag but found {}; set `shared-version`",
+            workspace_tags.into_iter().collect::<Vec<_>>().join(", ")
+        );
+    }
+
+    Ok(pkgs)
+}
+
+/// Plan the highest version within each `shared-version` group for all of its members
+fn share_versions(pkgs: &mut indexmap::IndexMap<cargo_metadata::PackageId, PackageRelease>) {
     let mut shared_versions: std::collections::HashMap<String, Version> = Default::default();
     for pkg in pkgs.values() {
         if !pkg.config.release() {
@@ -65,21 +122,76 @@ pub fn plan(
             }
         }
     }
+}
 
-    for pkg in pkgs.values_mut() {
-        pkg.plan()?;
+/// Release workspace dependents of planned releases according to their `dependent-bump` policy,
+/// returning whether any planned version changed
+fn cascade_dependents(
+    pkgs: &mut indexmap::IndexMap<cargo_metadata::PackageId, PackageRelease>,
+    deselected: &std::collections::HashSet<cargo_metadata::PackageId>,
+) -> CargoResult<bool> {
+    let mut changed = false;
+    // Dependencies are sorted before their dependents, so bumps cascade transitively
+    let ids = pkgs.keys().cloned().collect::<Vec<_>>();
+    for id in &ids {
+        let pkg = &pkgs[id];
+        if !pkg.config.release() || pkg.config.dependent_bump() == config::DependentBump::None {
+            continue;
+        }
+        let Some(version) = pkg.planned_version.as_ref() else {
+            continue;
+        };
+
+        let mut bumps = Vec::new();
+        for dependent in &pkg.dependents {
+            if let Some(level) = pkg.dependent_level(dependent, version)? {
+                bumps.push((dependent.pkg.id.clone(), level));
+            }
+        }
+        let crate_name = pkg.meta.name.clone();
+        let version = version.full_version_string.clone();
+
+        for (dependent_id, level) in bumps {
+            let Some(dependent) = pkgs.get_mut(&dependent_id) else {
+                continue;
+            };
+            let dependent_name = dependent.meta.name.as_str();
+            if !dependent.config.release() && !deselected.contains(&dependent_id) {
+                log::debug!("not releasing {dependent_name} for {crate_name} {version}, disabled");
+                continue;
+            }
+
+            let mut next = dependent.initial_version.full_version.clone();
+            level.bump_version(&mut next, None, dependent.config.pre_release_channels())?;
+            if dependent
+                .planned_version
+                .as_ref()
+                .is_some_and(|v| next <= v.full_version)
+            {
+                continue;
+            }
+            let _ = crate::ops::shell::status(
+                "Including",
+                format!("{dependent_name} {next} ({level} bump) for {crate_name} {version}"),
+            );
+            dependent.config.release = Some(true);
+            dependent.planned_version = Some(Version::from(next));
+            changed = true;
+        }
     }
 
-    Ok(pkgs)
+    Ok(changed)
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct PackageRelease {
     pub meta: cargo_metadata::Package,
     pub manifest_path: PathBuf,
     pub package_root: PathBuf,
     pub is_root: bool,
     pub config: config::Config,
+    /// The release group this is a member of
+    pub group: Option<String>,
 
     pub package_content: Vec<PathBuf>,
     pub bin: bool,
@@ -101,6 +213,7 @@ impl PackageRelease {
         git_root: &Path,
         ws_meta: &cargo_metadata::Metadata,
         pkg_meta: &cargo_metadata::Package,
+        group: Option<(&str, &config::Group)>,
     ) -> CargoResult<Self> {
         let meta = pkg_meta.clone();
         let manifest_path = pkg_meta.manifest_path.as_std_path().to_owned();
@@ -108,7 +221,7 @@ impl PackageRelease {
             .parent()
             .unwrap_or_else(|| Path::new("."))
             .to_owned();
-        let config = config::load_package_config(args, ws_meta, pkg_meta)?;
+        let config = config::load_package_config(args, ws_meta, pkg_meta, group)?;
         if !config.release() {
             log::trace!("disabled in config, skipping {}", manifest_path.display());
         }
@@ -185,6 +298,7 @@ impl PackageRelease {
             package_root,
             is_root,
             config,
+            group: group.map(|(name, _)| name.to_owned()),
 
             package_content,
             bin,
@@ -207,6 +321,7 @@ impl PackageRelease {
 
     pub fn bump<'s>(
         &'s mut self,
+        ws_meta: &cargo_metadata::Metadata,
         level_or_version: &super::TargetVersion,
         mut metadata: Option<&'s str>,
     ) -> CargoResult<()> {
@@ -233,11 +348,143 @@ impl PackageRelease {
                 }
             }
         }
-        self.planned_version =
-            level_or_version.bump(&self.initial_version.full_version, metadata)?;
+        let level_or_version = match level_or_version {
+            super::TargetVersion::Auto => match self.auto_level(ws_meta)? {
+                Some(level) => super::TargetVersion::Relative(level),
+                None => {
+                    self.planned_version = None;
+                    return Ok(());
+                }
+            },
+            super::TargetVersion::Relative(BumpLevel::Calver) => {
+                let mut version = self.initial_version.full_version.clone();
+                version
+                    .increment_calver(self.config.calver_format(), *crate::ops::replace::TODAY)?;
+                super::TargetVersion::Absolute(version)
+            }
+            level_or_version => level_or_version.clone(),
+        };
+        self.planned_version = level_or_version.bump(
+            &self.initial_version.full_version,
+            metadata,
+            self.config.pre_release_channels(),
+        )?;
         Ok(())
     }
 
+    /// Bump level for the conventional commits since [`PackageRelease::prior_tag`]
+    fn auto_level(&self, ws_meta: &cargo_metadata::Metadata) -> CargoResult<Option<BumpLevel>> {
+        let crate_name = self.meta.name.as_str();
+        let Some(prior_tag) = self.prior_tag.as_deref() else {
+            log::debug!(
+                "Cannot detect changes for {crate_name} because no tag was found. Try setting `--prev-tag-name <TAG>`.",
+            );
+            return Ok(None);
+        };
+
+        let workspace_root = ws_meta.workspace_root.as_std_path();
+        let commits = super::changes::package_commits(workspace_root, self, prior_tag)?;
+        self.suggested_level(&commits)
+    }
+
+    /// Bump level for `commits` made since [`PackageRelease::prior_tag`]
+    pub fn suggested_level(
+        &self,
+        commits: &[super::changes::PackageCommit],
+    ) -> CargoResult<Option<BumpLevel>> {
+        let crate_name = self.meta.name.as_str();
+        let version = &self.initial_version.full_version;
+        if version.is_prerelease() {
+            // Enough unknowns about pre-release to not bother
+            log::debug!("not bumping pre-release {crate_name} {version} automatically");
+            return Ok(None);
+        }
+
+        let Some(max_status) = super::changes::max_status(commits) else {
+            log::debug!("no release-worthy changes for {crate_name}");
+            return Ok(None);
+        };
+
+        let initial_tag = render_tag(
+            self.config.tag_name(),
+            self.config.tag_prefix(self.is_root),
+            crate_name,
+            &self.initial_version,
+            &self.initial_version,
+        );
+        let bumped = !git::tag_exists(&self.package_root, &initial_tag)?;
+        let level = super::changes::suggested_level(version, max_status, bumped);
+        log::debug!(
+            "{crate_name} has {max_status:?} changes, bumping by {}",
+            level
+                .as_ref()
+                .map(|l| l.to_string())
+                .unwrap_or_else(|| "nothing".to_owned())
+        );
+        Ok(level)
+    }
+
+    /// Whether `dependent`'s requirement on this package changes when releasing `version`, per
+    /// `dependent-version`
+    pub fn moves_requirement(&self, dependent: &Dependency, version: &Version) -> bool {
+        let compatible = dependent.req.matches(&version.full_version);
+        let updated = match self.config.dependent_version() {
+            config::DependentVersion::Fix if compatible => None,
+            config::DependentVersion::Fix | config::DependentVersion::Upgrade => {
+                crate::ops::version::upgrade_requirement(
+                    &dependent.req.to_string(),
+                    &version.full_version,
+                )
+                .ok()
+                .flatten()
+            }
+        };
+        updated.is_some()
+    }
+
+    /// Bump level `dependent` needs for this package being released as `version`
+    fn dependent_level(
+        &self,
+        dependent: &Dependency,
+        version: &Version,
+    ) -> CargoResult<Option<BumpLevel>> {
+        let crate_name = self.meta.name.as_str();
+        let kinds = dependent.kinds(crate_name);
+        if dependent.is_dev_only(crate_name) || !self.moves_requirement(dependent, version) {
+            // The dependent can pick this release up without being released itself
+            return Ok(None);
+        }
+
+        let compatible = dependent.req.matches(&version.full_version);
+        let level = match self.config.dependent_bump() {
+            config::DependentBump::None => None,
+            config::DependentBump::Patch => Some(BumpLevel::Patch),
+            config::DependentBump::Match if compatible => Some(BumpLevel::Patch),
+            config::DependentBump::Match => {
+                let public = if kinds.contains(&cargo_metadata::DependencyKind::Normal) {
+                    cargo::is_public_dependency(
+                        dependent.pkg.manifest_path.as_std_path(),
+                        crate_name,
+                    )?
+                    // Assume the worst when it isn't declared
+                    .unwrap_or(true)
+                } else {
+                    false
+                };
+                if public {
+                    super::changes::suggested_level(
+                        &dependent.pkg.version,
+                        super::changes::CommitStatus::Breaking,
+                        false,
+                    )
+                } else {
+                    Some(BumpLevel::Patch)
+                }
+            }
+        };
+        Ok(level)
+    }
+
     pub fn plan(&mut self) -> CargoResult<()> {
         if !self.config.release() {
             return Ok(());
@@ -328,12 +575,31 @@ fn find_dependents<'w>(
     })
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Dependency {
     pub pkg: cargo_metadata::Package,
     pub req: semver::VersionReq,
 }
 
+impl Dependency {
+    /// How `pkg` depends on `name`
+    pub fn kinds(&self, name: &str) -> Vec<cargo_metadata::DependencyKind> {
+        self.pkg
+            .dependencies
+            .iter()
+            .filter(|d| d.name == name)
+            .map(|d| d.kind)
+            .collect()
+    }
+
+    /// Whether `pkg` only uses `name` for its tests, leaving it out of its published requirements
+    pub fn is_dev_only(&self, name: &str) -> bool {
+        self.kinds(name)
+            .iter()
+            .all(|k| *k == cargo_metadata::DependencyKind::Development)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Version {
     pub full_version: semver::Version,
@@ -362,3 +628,502 @@ impl From<semver::Version> for Version {
         }
     }
 }
+
+/// Machine-readable summary of a release plan
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Report<'p> {
+    pub packages: Vec<PackageReport<'p>>,
+    pub checks: Vec<Check>,
+}
+
+impl<'p> Report<'p> {
+    pub fn new(
+        selected_pkgs: &'p [PackageRelease],
+        excluded_pkgs: &'p [PackageRelease],
+        checks: &Checks,
+    ) -> Self {
+        let packages = selected_pkgs
+            .iter()
+            .map(|pkg| PackageReport::new(pkg, true))
+            .chain(
+                excluded_pkgs
+                    .iter()
+                    .map(|pkg| PackageReport::new(pkg, false)),
+            )
+            .collect();
+        Self {
+            packages,
+            checks: checks.0.clone(),
+        }
+    }
+
+    pub fn to_json(&self) -> CargoResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Write the plan for hooks, see `RELEASE_PLAN`
+    pub fn persist(&self, ws_meta: &cargo_metadata::Metadata) -> CargoResult<PlanFile> {
+        let target_dir = ws_meta.target_directory.as_std_path();
+        std::fs::create_dir_all(target_dir)?;
+        let mut file = tempfile::Builder::new()
+            .prefix("release-plan-")
+            .suffix(".json")
+            .tempfile_in(target_dir)?;
+        std::io::Write::write_all(&mut file, self.to_json()?.as_bytes())?;
+        Ok(PlanFile(file))
+    }
+}
+
+/// A [`Report`] written for the duration of a release, removed when dropped
+#[derive(Debug)]
+pub struct PlanFile(tempfile::NamedTempFile);
+
+impl PlanFile {
+    pub fn path(&self) -> &Path {
+        self.0.path()
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackageReport<'p> {
+    pub name: &'p str,
+    pub selected: bool,
+    pub manifest_path: &'p Path,
+    pub initial_version: &'p str,
+    pub planned_version: Option<&'p str>,
+    pub prior_tag: Option<&'p str>,
+    pub planned_tag: Option<&'p str>,
+    pub publish: bool,
+    pub registry: Option<&'p str>,
+    pub ensure_owners: bool,
+    pub dependents: Vec<DependentReport<'p>>,
+    pub package_content: &'p [PathBuf],
+}
+
+impl<'p> PackageReport<'p> {
+    fn new(pkg: &'p PackageRelease, selected: bool) -> Self {
+        Self {
+            name: pkg.meta.name.as_str(),
+            selected,
+            manifest_path: &pkg.manifest_path,
+            initial_version: &pkg.initial_version.full_version_string,
+            planned_version: pkg
+                .planned_version
+                .as_ref()
+                .map(|v| v.full_version_string.as_str()),
+            prior_tag: pkg.prior_tag.as_deref(),
+            planned_tag: pkg.planned_tag.as_deref(),
+            publish: pkg.config.publish(),
+            registry: pkg.config.registry(),
+            ensure_owners: pkg.ensure_owners,
+            dependents: pkg
+                .dependents
+                .iter()
+                .map(|d| DependentReport {
+                    name: d.pkg.name.as_str(),
+                    req: d.req.to_string(),
+                })
+                .collect(),
+            package_content: &pkg.package_content,
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DependentReport<'p> {
+    pub name: &'p str,
+    pub req: String,
+}
+
+/// Results of the `verify_*` checks
+#[derive(Clone, Debug, Default)]
+pub struct Checks(Vec<Check>);
+
+impl Checks {
+    /// Record the result of a check, returning whether it succeeded
+    pub fn record(&mut self, name: &'static str, success: bool) -> bool {
+        self.0.push(Check { name, success });
+        success
+    }
+
+    pub fn failed(&self) -> bool {
+        self.0.iter().any(|c| !c.success)
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Check {
+    pub name: &'static str,
+    pub success: bool,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod load {
+        use super::*;
+
+        use crate::steps::fixture::{Workspace, id};
+
+        #[test]
+        fn groups() {
+            let ws = Workspace::new(
+                r#"
+[groups.core]
+members = ["core*"]
+shared-version = true
+tag-name = "core-v{{version}}"
+"#,
+                &[
+                    ("core", "0.1.0", &[]),
+                    ("core-derive", "0.2.0", &[]),
+                    ("cli", "0.3.0", &[]),
+                ],
+            );
+            let ws_meta = ws.metadata();
+            let pkgs = ws.load(&ws_meta);
+
+            for name in ["core", "core-derive"] {
+                let pkg = &pkgs[id(&pkgs, name)];
+                assert_eq!(pkg.group.as_deref(), Some("core"));
+                assert_eq!(pkg.config.shared_version(), Some("core"));
+                assert_eq!(pkg.config.tag_name(), "core-v{{version}}");
+            }
+            let cli = &pkgs[id(&pkgs, "cli")];
+            assert_eq!(cli.group, None);
+            assert_eq!(cli.config.shared_version(), None);
+
+            let planned = ws.plan(&ws_meta, BumpLevel::Patch);
+            let tags = planned
+                .iter()
+                .map(|pkg| pkg.planned_tag.as_deref().unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(tags, ["core-v0.2.1", "core-v0.2.1", "cli-v0.3.1"]);
+        }
+    }
+
+    mod auto_level {
+        use super::*;
+
+        use crate::steps::fixture::{Workspace, id};
+
+        /// The level `auto` picks for `foo` at `version` after committing `messages`
+        fn auto_level(version: &str, messages: &[&str]) -> Option<BumpLevel> {
+            let ws = Workspace::new("", &[("foo", version, &[])]);
+            ws.tag(&format!("foo-v{version}"));
+            for (i, message) in messages.iter().enumerate() {
+                ws.write("foo/src/lib.rs", &format!("// {i}\n"));
+                ws.commit(message);
+            }
+            let ws_meta = ws.metadata();
+            let pkgs = ws.load(&ws_meta);
+            pkgs[id(&pkgs, "foo")].auto_level(&ws_meta).unwrap()
+        }
+
+        #[test]
+        fn feature() {
+            assert_eq!(
+                auto_level("1.2.3", &["fix: Handle empty input", "feat: Add flag"]),
+                Some(BumpLevel::Minor)
+            );
+        }
+
+        #[test]
+        fn fix() {
+            assert_eq!(
+                auto_level("1.2.3", &["chore: Tidy", "fix: Handle empty input"]),
+                Some(BumpLevel::Patch)
+            );
+        }
+
+        #[test]
+        fn breaking() {
+            assert_eq!(
+                auto_level("1.2.3", &["feat: Add flag", "refactor!: Drop old API"]),
+                Some(BumpLevel::Major)
+            );
+        }
+
+        #[test]
+        fn breaking_before_1_0() {
+            assert_eq!(
+                auto_level("0.3.1", &["feat!: Drop old API"]),
+                Some(BumpLevel::Minor)
+            );
+            assert_eq!(
+                auto_level("0.3.1", &["feat: Add flag"]),
+                Some(BumpLevel::Patch)
+            );
+        }
+
+        #[test]
+        fn no_commits() {
+            assert_eq!(auto_level("1.2.3", &[]), None);
+        }
+
+        #[test]
+        fn ignored_commits() {
+            assert_eq!(auto_level("1.2.3", &["chore: Tidy", "test: Cover"]), None);
+        }
+
+        #[test]
+        fn no_prior_tag() {
+            let ws = Workspace::new("", &[("foo", "1.2.3", &[])]);
+            ws.commit("feat: Add flag");
+            let ws_meta = ws.metadata();
+            let pkgs = ws.load(&ws_meta);
+            assert_eq!(pkgs[id(&pkgs, "foo")].auto_level(&ws_meta).unwrap(), None);
+        }
+    }
+
+    mod workspace_tag {
+        use super::*;
+
+        use crate::steps::fixture::{Workspace, id};
+
+        const CONFIG: &str = "workspace-tag = true\nshared-version = true\n";
+
+        #[test]
+        fn single_tag() {
+            let ws = Workspace::new(CONFIG, &[("foo", "0.1.0", &[]), ("bar", "0.1.0", &[])]);
+            let ws_meta = ws.metadata();
+            let planned = ws.plan(&ws_meta, BumpLevel::Minor);
+            let tags = planned
+                .iter()
+                .map(|pkg| pkg.planned_tag.as_deref().unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(tags, ["v0.2.0", "v0.2.0"]);
+        }
+
+        #[test]
+        fn versions_differ() {
+            let ws = Workspace::new(
+                "workspace-tag = true\n",
+                &[("foo", "0.1.0", &[]), ("bar", "0.2.0", &[])],
+            );
+            let ws_meta = ws.metadata();
+            let mut pkgs = ws.load(&ws_meta);
+            for pkg in pkgs.values_mut() {
+                pkg.bump(
+                    &ws_meta,
+                    &crate::steps::TargetVersion::Relative(BumpLevel::Patch),
+                    None,
+                )
+                .unwrap();
+            }
+            let err = plan(pkgs).unwrap_err().to_string();
+            assert!(err.contains("v0.1.1, v0.2.1"), "{err}");
+            assert!(err.contains("`shared-version`"), "{err}");
+        }
+
+        #[test]
+        fn mixed() {
+            let ws = Workspace::new(CONFIG, &[("foo", "0.1.0", &[]), ("bar", "0.1.0", &[])]);
+            let ws_meta = ws.metadata();
+            let mut pkgs = ws.load(&ws_meta);
+            let bar = id(&pkgs, "bar").clone();
+            pkgs[&bar].config.workspace_tag = Some(false);
+            let err = plan(pkgs).unwrap_err().to_string();
+            assert!(err.contains("inconsistent `workspace-tag`"), "{err}");
+        }
+
+        #[test]
+        fn prior_tag() {
+            let ws = Workspace::new(CONFIG, &[("foo", "0.1.0", &[]), ("bar", "0.1.0", &[])]);
+            ws.tag("foo-v0.1.0");
+            ws.tag("v0.1.0");
+            let ws_meta = ws.metadata();
+            let pkgs = ws.load(&ws_meta);
+            for name in ["foo", "bar"] {
+                assert_eq!(
+                    pkgs[id(&pkgs, name)].prior_tag.as_deref(),
+                    Some("v0.1.0"),
+                    "{name}"
+                );
+            }
+
+            // Without a tag for the current version, fall back to the latest workspace tag
+            ws.write(
+                "foo/Cargo.toml",
+                &ws.read("foo/Cargo.toml").replace("0.1.0", "0.1.1"),
+            );
+            ws.write(
+                "bar/Cargo.toml",
+                &ws.read("bar/Cargo.toml").replace("0.1.0", "0.1.1"),
+            );
+            ws.commit("chore: Bump");
+            let ws_meta = ws.metadata();
+            let pkgs = ws.load(&ws_meta);
+            assert_eq!(pkgs[id(&pkgs, "foo")].prior_tag.as_deref(), Some("v0.1.0"));
+        }
+    }
+
+    mod plan_with_dependents {
+        use super::*;
+
+        use crate::steps::fixture::{Workspace, id};
+
+        #[test]
+        fn shared_version_dependent() {
+            let ws = Workspace::new(
+                "dependent-bump = \"patch\"",
+                &[
+                    ("foo", "0.1.0", &[]),
+                    ("qux", "0.2.0", &[]),
+                    ("bar", "0.1.0", &[("foo", "0.1")]),
+                ],
+            );
+            let ws_meta = ws.metadata();
+            let mut pkgs = ws.load(&ws_meta);
+            let bar = id(&pkgs, "bar").clone();
+            for pkg in pkgs.values_mut() {
+                if pkg.meta.id == bar {
+                    pkg.config.release = Some(false);
+                } else {
+                    pkg.config.shared_version = Some(config::SharedVersion::Name("g".to_owned()));
+                    pkg.bump(
+                        &ws_meta,
+                        &crate::steps::TargetVersion::Relative(BumpLevel::Patch),
+                        None,
+                    )
+                    .unwrap();
+                }
+            }
+
+            // `foo` alone would be 0.1.1, still compatible with `bar`'s requirement
+            let deselected = std::collections::HashSet::from([bar.clone()]);
+            let pkgs = plan_with_dependents(pkgs, &deselected).unwrap();
+            let planned = |name: &str| {
+                pkgs[id(&pkgs, name)]
+                    .planned_version
+                    .as_ref()
+                    .map(|v| v.full_version_string.clone())
+            };
+            assert_eq!(planned("foo").as_deref(), Some("0.2.1"));
+            assert_eq!(planned("qux").as_deref(), Some("0.2.1"));
+            assert_eq!(planned("bar").as_deref(), Some("0.1.1"));
+            assert!(pkgs[&bar].config.release());
+        }
+
+        #[test]
+        fn excluded_dependent() {
+            let ws = Workspace::new(
+                "dependent-bump = \"patch\"",
+                &[("foo", "0.1.0", &[]), ("bar", "0.1.0", &[("foo", "0.1")])],
+            );
+            let ws_meta = ws.metadata();
+            let mut pkgs = ws.load(&ws_meta);
+            let bar = id(&pkgs, "bar").clone();
+            pkgs[&bar].config.release = Some(false);
+            let foo = id(&pkgs, "foo").clone();
+            pkgs[&foo]
+                .bump(
+                    &ws_meta,
+                    &crate::steps::TargetVersion::Relative(BumpLevel::Minor),
+                    None,
+                )
+                .unwrap();
+
+            let pkgs = plan_with_dependents(pkgs, &Default::default()).unwrap();
+            assert!(!pkgs[&bar].config.release());
+            assert!(pkgs[&bar].planned_version.is_none());
+        }
+    }
+
+    mod report {
+        use super::*;
+
+        use crate::steps::BumpLevel;
+        use crate::steps::fixture::Workspace;
+
+        #[test]
+        fn serialized() {
+            let ws = Workspace::new(
+                "",
+                &[("foo", "0.1.0", &[]), ("bar", "0.1.0", &[("foo", "0.1")])],
+            );
+            ws.tag("foo-v0.1.0");
+            let ws_meta = ws.metadata();
+            let pkgs = ws.plan(&ws_meta, BumpLevel::Minor);
+            let (selected, excluded): (Vec<_>, Vec<_>) = pkgs
+                .into_iter()
+                .partition(|pkg| pkg.meta.name.as_str() == "foo");
+            let mut checks = Checks::default();
+            assert!(checks.record("git-branch", true));
+            assert!(!checks.record("metadata", false));
+            assert!(checks.failed());
+
+            let report = Report::new(&selected, &excluded, &checks);
+            let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+            let foo = &selected[0];
+            let bar = &excluded[0];
+            assert_eq!(
+                json,
+                serde_json::json!({
+                    "packages": [
+                        {
+                            "name": "foo",
+                            "selected": true,
+                            "manifest-path": foo.manifest_path,
+                            "initial-version": "0.1.0",
+                            "planned-version": "0.2.0",
+                            "prior-tag": "foo-v0.1.0",
+                            "planned-tag": "foo-v0.2.0",
+                            "publish": true,
+                            "registry": null,
+                            "ensure-owners": false,
+                            "dependents": [{ "name": "bar", "req": "^0.1" }],
+                            "package-content": foo.package_content,
+                        },
+                        {
+                            "name": "bar",
+                            "selected": false,
+                            "manifest-path": bar.manifest_path,
+                            "initial-version": "0.1.0",
+                            "planned-version": "0.2.0",
+                            "prior-tag": null,
+                            "planned-tag": "bar-v0.2.0",
+                            "publish": true,
+                            "registry": null,
+                            "ensure-owners": false,
+                            "dependents": [],
+                            "package-content": bar.package_content,
+                        },
+                    ],
+                    "checks": [
+                        { "name": "git-branch", "success": true },
+                        { "name": "metadata", "success": false },
+                    ],
+                })
+            );
+        }
+
+        #[test]
+        fn persist_lasts_until_dropped() {
+            let ws = Workspace::new("", &[("foo", "0.1.0", &[])]);
+            let ws_meta = ws.metadata();
+            let pkgs = ws.plan(&ws_meta, BumpLevel::Minor);
+            let report = Report::new(&pkgs, &[], &Checks::default());
+
+            let plan_file = report.persist(&ws_meta).unwrap();
+            let path = plan_file.path().to_owned();
+            assert!(path.starts_with(ws_meta.target_directory.as_std_path()));
+            assert_eq!(
+                std::fs::read_to_string(&path).unwrap(),
+                report.to_json().unwrap()
+            );
+
+            // Each run gets its own file
+            let other = report.persist(&ws_meta).unwrap();
+            assert_ne!(other.path(), path);
+
+            drop(plan_file);
+            assert!(!path.exists());
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use crate::config;
+use crate::error::CargoResult;
+use crate::error::CliError;
+use crate::ops::cargo;
+use crate::ops::git;
+use crate::ops::replace::{NOW, Template, do_file_replacements};
+use crate::steps::plan;
+
+/// Move released packages on to their `post-release-version` and commit it
+pub fn post_release(
+    ws_meta: &cargo_metadata::Metadata,
+    ws_config: &config::Config,
+    selected_pkgs: &[plan::PackageRelease],
+    excluded_pkgs: &[plan::PackageRelease],
+    dry_run: bool,
+) -> Result<(), CliError> {
+    let mut pkgs = Vec::new();
+    for pkg in selected_pkgs {
+        if let Some(version) = post_release_version(pkg)? {
+            let released = pkg.planned_version.as_ref().unwrap_or(&pkg.initial_version);
+            let mut pkg = pkg.clone();
+            pkg.initial_version = released.clone();
+            pkg.planned_version = Some(version);
+            pkgs.push(pkg);
+        }
+    }
+    if pkgs.is_empty() {
+        return Ok(());
+    }
+
+    if ws_config.consolidate_commits() {
+        let update_lock = super::version::update_versions(ws_meta, &pkgs, excluded_pkgs, dry_run)?;
+        if update_lock {
+            log::debug!("updating lock file");
+            if !dry_run {
+                let workspace_path = ws_meta.workspace_root.as_std_path().join("Cargo.toml");
+                cargo::update_lock(&workspace_path)?;
+            }
+        }
+
+        for pkg in &pkgs {
+            replace(pkg, dry_run)?;
+        }
+
+        let shared_version = super::find_shared_versions(&pkgs)?;
+        let template = Template {
+            version: shared_version
+                .as_ref()
+                .map(|v| v.bare_version_string.as_str()),
+            metadata: shared_version
+                .as_ref()
+                .map(|v| v.full_version.build.as_str()),
+            date: Some(NOW.as_str()),
+            ..Default::default()
+        };
+        let commit_msg = template.render(ws_config.post_release_commit_message());
+        if !git::commit_all(
+            ws_meta.workspace_root.as_std_path(),
+            &commit_msg,
+            ws_config.sign_commit(),
+            dry_run,
+        )? {
+            // commit failed, abort release
+            return Err(101.into());
+        }
+    } else {
+        for pkg in &pkgs {
+            let crate_name = pkg.meta.name.as_str();
+            let version = pkg.planned_version.as_ref().expect("always set above");
+            let _ = crate::ops::shell::status(
+                "Upgrading",
+                format!(
+                    "{} from {} to {}",
+                    crate_name,
+                    pkg.initial_version.full_version_string,
+                    version.full_version_string
+                ),
+            );
+            cargo::set_package_version(
+                &pkg.manifest_path,
+                version.full_version_string.as_str(),
+                dry_run,
+            )?;
+            super::version::update_dependent_versions(ws_meta, pkg, version, dry_run)?;
+            if dry_run {
+                log::debug!("updating lock file");
+            } else {
+                cargo::update_lock(&pkg.manifest_path)?;
+            }
+
+            replace(pkg, dry_run)?;
+
+            let template = Template {
+                prev_version: Some(pkg.initial_version.bare_version_string.as_str()),
+                prev_metadata: Some(pkg.initial_version.full_version.build.as_str()),
+                version: Some(version.bare_version_string.as_str()),
+                metadata: Some(version.full_version.build.as_str()),
+                crate_name: Some(crate_name),
+                date: Some(NOW.as_str()),
+                ..Default::default()
+            };
+            let commit_msg = template.render(pkg.config.post_release_commit_message());
+            if !git::commit_all(
+                &pkg.package_root,
+                &commit_msg,
+                pkg.config.sign_commit(),
+                dry_run,
+            )? {
+                // commit failed, abort release
+                return Err(101.into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Render `post-release-version` for the version being released
+pub fn post_release_version(pkg: &plan::PackageRelease) -> CargoResult<Option<plan::Version>> {
+    let Some(pattern) = pkg.config.post_release_version() else {
+        return Ok(None);
+    };
+    let crate_name = pkg.meta.name.as_str();
+    let released = &pkg
+        .planned_version
+        .as_ref()
+        .unwrap_or(&pkg.initial_version)
+        .full_version;
+
+    let mut rendered = String::new();
+    let mut rest = pattern;
+    while let Some(start) = rest.find("{{")
+        && let Some(len) = rest[start..].find("}}")
+    {
+        let name = &rest[start + 2..start + len];
+        let value = match name {
+            "major" => released.major,
+            "minor" => released.minor,
+            "patch" => released.patch,
+            "next_major" => released.major + 1,
+            "next_minor" => released.minor + 1,
+            "next_patch" => released.patch + 1,
+            _ => anyhow::bail!(
+                "unknown placeholder `{{{{{name}}}}}` in `post-release-version` for {crate_name}, expected one of `{{{{major}}}}`, `{{{{minor}}}}`, `{{{{patch}}}}`, `{{{{next_major}}}}`, `{{{{next_minor}}}}`, `{{{{next_patch}}}}`"
+            ),
+        };
+        rendered.push_str(&rest[..start]);
+        rendered.push_str(&value.to_string());
+        rest = &rest[start + len + 2..];
+    }
+    rendered.push_str(rest);
+    let version = semver::Version::parse(&rendered).map_err(|e| {
+        anyhow::format_err!(
+            "`post-release-version` for {crate_name} rendered to `{rendered}` which is not a valid version: {e}"
+        )
+    })?;
+    if version <= *released {
+        anyhow::bail!(
+            "`post-release-version` for {crate_name} must be greater than {released}, got {version}"
+        );
+    }
+
+    Ok(Some(plan::Version::from(version)))
+}
+
+fn replace(pkg: &plan::PackageRelease, dry_run: bool) -> Result<(), CliError> {
+    if pkg.config.post_release_replacements().is_empty() {
+        return Ok(());
+    }
+
+    let version = pkg.planned_version.as_ref().unwrap_or(&pkg.initial_version);
+    let template = Template {
+        prev_version: Some(pkg.initial_version.bare_version_string.as_str()),
+        prev_metadata: Some(pkg.initial_version.full_version.build.as_str()),
+        version: Some(version.bare_version_string.as_str()),
+        metadata: Some(version.full_version.build.as_str()),
+        crate_name: Some(pkg.meta.name.as_str()),
+        repository: pkg.meta.repository.as_deref(),
+        date: Some(NOW.as_str()),
+        tag_name: pkg.planned_tag.as_deref(),
+        ..Default::default()
+    };
+    // Development versions are usually pre-releases, so don't make users opt-in every entry
+    let prerelease = false;
+    let noisy = true;
+    do_file_replacements(
+        pkg.config.post_release_replacements(),
+        &template,
+        &pkg.package_root,
+        prerelease,
+        noisy,
+        dry_run,
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod post_release_version {
+        use super::*;
+
+        use crate::steps::fixture::{Workspace, id};
+
+        fn render(pattern: &str) -> CargoResult<Option<String>> {
+            let ws = Workspace::new("", &[("foo", "1.4.0", &[])]);
+            let ws_meta = ws.metadata();
+            let pkgs = ws.load(&ws_meta);
+            let mut pkg = pkgs[id(&pkgs, "foo")].clone();
+            pkg.config.post_release_version = Some(pattern.to_owned());
+            pkg.planned_version = Some(semver::Version::new(1, 4, 2).into());
+            post_release_version(&pkg).map(|v| v.map(|v| v.full_version_string))
+        }
+
+        #[test]
+        fn unset() {
+            let ws = Workspace::new("", &[("foo", "1.4.0", &[])]);
+            let ws_meta = ws.metadata();
+            let pkgs = ws.load(&ws_meta);
+            assert!(
+                post_release_version(&pkgs[id(&pkgs, "foo")])
+                    .unwrap()
+                    .is_none()
+            );
+        }
+
+        #[test]
+        fn placeholders() {
+            assert_eq!(
+                render("{{major}}.{{minor}}.{{next_patch}}-dev")
+                    .unwrap()
+                    .as_deref(),
+                Some("1.4.3-dev")
+            );
+            assert_eq!(
+                render("{{major}}.{{next_minor}}.0-alpha.0")
+                    .unwrap()
+                    .as_deref(),
+                Some("1.5.0-alpha.0")
+            );
+            assert_eq!(
+                render("{{next_major}}.0.{{patch}}").unwrap().as_deref(),
+                Some("2.0.2")
+            );
+        }
+
+        #[test]
+        fn unknown_placeholder() {
+            let err = render("{{major}}.{{minor}}.{{next_pach}}-dev")
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("unknown placeholder `{{next_pach}}`"), "{err}");
+            assert!(err.contains("`{{next_patch}}`"), "{err}");
+        }
+
+        #[test]
+        fn invalid_version() {
+            let err = render("{{major}}.{{minor}}").unwrap_err().to_string();
+            assert!(err.contains("rendered to `1.4`"), "{err}");
+        }
+
+        #[test]
+        fn not_greater() {
+            let err = render("{{major}}.{{minor}}.{{patch}}-dev")
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("must be greater than 1.4.2"), "{err}");
+        }
+    }
+}
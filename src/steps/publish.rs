@@ -2,6 +2,7 @@ use itertools::Itertools;
 
 use crate::error::CliError;
 use crate::ops::git;
+use crate::ops::rate_limit::{self, Bucket};
 use crate::steps::plan;
 
 /// Publish the specified packages
@@ -15,6 +16,9 @@ pub struct PublishStep {
     #[command(flatten)]
     workspace: clap_cargo::Workspace,
 
+    #[command(flatten)]
+    group: super::GroupArgs,
+
     /// Custom config file
     #[arg(short, long = "config", value_name = "PATH")]
     custom_config: Option<std::path::PathBuf>,
@@ -63,9 +67,10 @@ impl PublishStep {
             .exec()?;
         let config = self.to_config();
         let ws_config = crate::config::load_workspace_config(&config, &ws_meta)?;
+        let workspace = self.group.select(&self.workspace, &ws_config, &ws_meta)?;
         let mut pkgs = plan::load(&config, &ws_meta)?;
 
-        let (_selected_pkgs, excluded_pkgs) = self.workspace.partition_packages(&ws_meta);
+        let (_selected_pkgs, excluded_pkgs) = workspace.partition_packages(&ws_meta);
         for excluded_pkg in excluded_pkgs {
             let Some(pkg) = pkgs.get_mut(&excluded_pkg.id) else {
                 // Either not in workspace or marked as `release = false`.
@@ -152,7 +157,13 @@ impl PublishStep {
         super::confirm("Publish", &selected_pkgs, self.no_confirm, dry_run)?;
 
         // STEP 3: cargo publish
-        publish(&selected_pkgs, dry_run)?;
+        publish(
+            &mut index,
+            &ws_config.rate_limit,
+            &selected_pkgs,
+            dry_run,
+            |_| Ok(()),
+        )?;
 
         super::finish(failed, dry_run)
     }
@@ -169,23 +180,70 @@ impl PublishStep {
     }
 }
 
-pub fn publish(pkgs: &[plan::PackageRelease], dry_run: bool) -> Result<(), CliError> {
+/// crates.io: "The rate limit for creating new crates is 1 crate every 10 minutes"
+pub const NEW_PACKAGE_REFILL: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+/// crates.io: "The rate limit for new versions of existing crates is 1 per minute"
+pub const EXISTING_PACKAGE_REFILL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Give up on a crate after the registry has rate limited it this many times
+const MAX_RATE_LIMIT_RETRIES: usize = 5;
+
+/// Publish `pkgs`, calling `published` for each package once it is done
+pub fn publish(
+    index: &mut crate::ops::index::CratesIoIndex,
+    rate_limit: &crate::config::RateLimit,
+    pkgs: &[plan::PackageRelease],
+    dry_run: bool,
+    mut published: impl FnMut(&plan::PackageRelease) -> crate::error::CargoResult<()>,
+) -> Result<(), CliError> {
     if pkgs.is_empty() {
         Ok(())
     } else {
         let first_pkg = pkgs.first().unwrap();
         let registry = first_pkg.config.registry();
         let target = first_pkg.config.target.as_deref();
-        let publish_grace_sleep = publish_grace_sleep();
-        if publish_grace_sleep.is_none()
-            && pkgs
-                .iter()
-                .all(|p| p.config.registry() == registry && p.config.target.as_deref() == target)
+        if rate_limit.schedule() {
+            let schedule = Schedule::new(rate_limit, std::time::Instant::now());
+            serial_publish(index, Some(schedule), pkgs, dry_run, &mut published)
+        } else if pkgs
+            .iter()
+            .all(|p| p.config.registry() == registry && p.config.target.as_deref() == target)
         {
             let manifest_path = &first_pkg.manifest_path;
-            workspace_publish(manifest_path, pkgs, registry, target, dry_run)
+            workspace_publish(
+                manifest_path,
+                pkgs,
+                registry,
+                target,
+                dry_run,
+                &mut published,
+            )
         } else {
-            serial_publish(pkgs, publish_grace_sleep, dry_run)
+            serial_publish(index, None, pkgs, dry_run, &mut published)
+        }
+    }
+}
+
+/// Rate limits for the default registry
+struct Schedule {
+    new: Bucket,
+    existing: Bucket,
+}
+
+impl Schedule {
+    fn new(rate_limit: &crate::config::RateLimit, now: std::time::Instant) -> Self {
+        Self {
+            new: Bucket::new(rate_limit.new_packages(), NEW_PACKAGE_REFILL, now),
+            existing: Bucket::new(rate_limit.existing_packages(), EXISTING_PACKAGE_REFILL, now),
+        }
+    }
+
+    /// The limit for publishing a crate, depending on whether it `exists` in the registry
+    fn bucket(&mut self, exists: bool) -> &mut Bucket {
+        if exists {
+            &mut self.existing
+        } else {
+            &mut self.new
         }
     }
 }
@@ -196,6 +254,7 @@ fn workspace_publish(
     registry: Option<&str>,
     target: Option<&str>,
     dry_run: bool,
+    published: &mut impl FnMut(&plan::PackageRelease) -> crate::error::CargoResult<()>,
 ) -> Result<(), CliError> {
     let crate_names = pkgs.iter().map(|p| p.meta.name.as_str()).join(", ");
     let _ = crate::ops::shell::status("Publishing", crate_names);
@@ -223,22 +282,41 @@ fn workspace_publish(
     )? {
         return Err(101.into());
     }
+    for pkg in pkgs {
+        published(pkg)?;
+    }
 
     Ok(())
 }
 
 fn serial_publish(
+    index: &mut crate::ops::index::CratesIoIndex,
+    mut schedule: Option<Schedule>,
     pkgs: &[plan::PackageRelease],
-    publish_grace_sleep: Option<u64>,
     dry_run: bool,
+    published: &mut impl FnMut(&plan::PackageRelease) -> crate::error::CargoResult<()>,
 ) -> Result<(), CliError> {
     for pkg in pkgs {
         if !pkg.config.publish() {
+            published(pkg)?;
             continue;
         }
 
         let crate_name = pkg.meta.name.as_str();
-        let _ = crate::ops::shell::status("Publishing", crate_name);
+
+        // Note: these rate limits are only known for default registry
+        let bucket = match schedule.as_mut() {
+            Some(schedule) if pkg.config.registry().is_none() => {
+                let exists = crate::ops::cargo::crate_exists(
+                    index,
+                    None,
+                    crate_name,
+                    pkg.config.certs_source(),
+                );
+                Some(schedule.bucket(exists))
+            }
+            _ => None,
+        };
 
         let verify = if !pkg.config.verify() {
             false
@@ -256,41 +334,501 @@ fn serial_publish(
         // atm at least Cargo doesn't seem to mind if `crate_name` is also a transitive dep, unlike
         // other cargo commands
         let pkgid = &[crate_name];
-        if !crate::ops::cargo::publish(
-            dry_run,
-            verify,
-            &pkg.manifest_path,
-            pkgid,
-            features,
-            pkg.config.registry(),
-            pkg.config.target.as_ref().map(AsRef::as_ref),
-        )? {
-            return Err(101.into());
+
+        if let Some(bucket) = bucket {
+            publish_within_rate_limit(
+                crate_name,
+                bucket,
+                dry_run,
+                || {
+                    crate::ops::cargo::publish_capture_output(
+                        dry_run,
+                        verify,
+                        &pkg.manifest_path,
+                        crate_name,
+                        features,
+                        pkg.config.registry(),
+                        pkg.config.target.as_ref().map(AsRef::as_ref),
+                    )
+                },
+                std::thread::sleep,
+            )?;
+        } else {
+            let _ = crate::ops::shell::status("Publishing", crate_name);
+            if !crate::ops::cargo::publish(
+                dry_run,
+                verify,
+                &pkg.manifest_path,
+                pkgid,
+                features,
+                pkg.config.registry(),
+                pkg.config.target.as_ref().map(AsRef::as_ref),
+            )? {
+                return Err(101.into());
+            }
         }
 
-        // HACK: This is a fallback in case users can't or don't want to rely on cargo waiting for
-        // them
-        if !dry_run && let Some(publish_grace_sleep) = publish_grace_sleep {
-            log::debug!(
-                "waiting an additional {} seconds for {} to update its indices...",
-                publish_grace_sleep,
-                pkg.config.registry().unwrap_or("crates.io")
-            );
-            std::thread::sleep(std::time::Duration::from_secs(publish_grace_sleep));
+        if !dry_run {
+            wait_for_publish(index, pkg)?;
         }
+        published(pkg)?;
     }
 
     Ok(())
 }
 
-fn publish_grace_sleep() -> Option<u64> {
-    let publish_grace_sleep = std::env::var("PUBLISH_GRACE_SLEEP")
-        .unwrap_or_else(|_| Default::default())
-        .parse()
-        .unwrap_or(0);
-    if publish_grace_sleep == 0 {
-        None
-    } else {
-        Some(publish_grace_sleep)
+/// Publish a crate once `bucket` allows it, retrying when the registry rate limits it anyway
+fn publish_within_rate_limit(
+    crate_name: &str,
+    bucket: &mut Bucket,
+    dry_run: bool,
+    mut publish: impl FnMut() -> crate::error::CargoResult<crate::ops::cmd::Output>,
+    mut sleep: impl FnMut(std::time::Duration),
+) -> Result<(), CliError> {
+    let mut retries = 0;
+    loop {
+        let wait = bucket.reserve(std::time::Instant::now());
+        if !wait.is_zero() {
+            let _ = crate::ops::shell::status(
+                "Waiting",
+                format!(
+                    "{}s to publish {} within the crates.io rate limit",
+                    wait.as_secs(),
+                    crate_name
+                ),
+            );
+            if !dry_run {
+                sleep(wait);
+            }
+        }
+
+        let _ = crate::ops::shell::status("Publishing", crate_name);
+        let output = publish()?;
+        if output.success() {
+            return Ok(());
+        }
+        let Some(retry_after) =
+            rate_limit::retry_after(&output.output, time::OffsetDateTime::now_utc())
+        else {
+            return Err(101.into());
+        };
+        if MAX_RATE_LIMIT_RETRIES <= retries {
+            let _ = crate::ops::shell::error(format!(
+                "giving up on {crate_name} after being rate limited {retries} times"
+            ));
+            return Err(101.into());
+        }
+        retries += 1;
+        let retry_after = retry_after.unwrap_or_else(|| bucket.refill());
+        let _ = crate::ops::shell::warn(format!(
+            "rate limited while publishing {crate_name}, retrying in {}s",
+            retry_after.as_secs()
+        ));
+        bucket.pause(std::time::Instant::now(), retry_after);
+    }
+}
+
+/// Poll the index until the planned version of `pkg` is available to dependents
+fn wait_for_publish(
+    index: &mut crate::ops::index::CratesIoIndex,
+    pkg: &plan::PackageRelease,
+) -> Result<(), CliError> {
+    let crate_name = pkg.meta.name.as_str();
+    let version = pkg.planned_version.as_ref().unwrap_or(&pkg.initial_version);
+    let registry = pkg.config.registry().unwrap_or("crates.io");
+    let poll = Poll {
+        timeout: std::time::Duration::from_secs(pkg.config.publish_timeout()),
+        interval: std::time::Duration::from_secs(pkg.config.publish_poll_interval()),
+    };
+
+    let start = std::time::Instant::now();
+    poll.run(
+        &format!("{} {}", crate_name, version.full_version_string),
+        registry,
+        || {
+            index.update_krate(pkg.config.registry(), crate_name);
+            index.has_krate_version(
+                pkg.config.registry(),
+                crate_name,
+                &version.full_version_string,
+                pkg.config.certs_source(),
+            )
+        },
+        || start.elapsed(),
+        std::thread::sleep,
+    )
+}
+
+/// Backoff for polling the index after publishing
+struct Poll {
+    timeout: std::time::Duration,
+    interval: std::time::Duration,
+}
+
+impl Poll {
+    const MAX_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+    /// Call `check` until it reports `what` is on `registry`, doubling the wait between checks
+    ///
+    /// Fails once `elapsed` passes the timeout, including the last error from `check`, if any.
+    fn run(
+        &self,
+        what: &str,
+        registry: &str,
+        mut check: impl FnMut() -> Result<Option<bool>, CliError>,
+        mut elapsed: impl FnMut() -> std::time::Duration,
+        mut sleep: impl FnMut(std::time::Duration),
+    ) -> Result<(), CliError> {
+        let mut interval = self.interval;
+        let mut last_err = None;
+        loop {
+            match check() {
+                Ok(Some(true)) => {
+                    log::debug!("{what} is available on {registry}");
+                    return Ok(());
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    log::debug!("failed to check {registry} for {what}: {err}");
+                    last_err = Some(err);
+                }
+            }
+
+            let elapsed = elapsed();
+            if self.timeout <= elapsed {
+                let mut msg = format!(
+                    "timed out after {}s waiting for {what} to be available on {registry}",
+                    elapsed.as_secs(),
+                );
+                if let Some(last_err) = last_err {
+                    msg.push_str(&format!(": {last_err}"));
+                }
+                return Err(anyhow::Error::msg(msg).into());
+            }
+            let _ = crate::ops::shell::status(
+                "Waiting",
+                format!(
+                    "for {what} to be available on {registry} ({}s elapsed)",
+                    elapsed.as_secs()
+                ),
+            );
+            sleep(interval.min(self.timeout - elapsed));
+            interval = (interval * 2).min(Self::MAX_INTERVAL);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod schedule {
+        use super::*;
+
+        use std::time::{Duration, Instant};
+
+        #[test]
+        fn waits_per_bucket() {
+            let start = Instant::now();
+            let rate_limit = crate::config::RateLimit {
+                new_packages: Some(2),
+                existing_packages: Some(3),
+                ..Default::default()
+            };
+            let mut schedule = Schedule::new(&rate_limit, start);
+
+            assert_eq!(schedule.bucket(false).reserve(start), Duration::ZERO);
+            assert_eq!(schedule.bucket(false).reserve(start), Duration::ZERO);
+            assert_eq!(schedule.bucket(false).reserve(start), NEW_PACKAGE_REFILL);
+
+            // New crates don't hold up new versions of existing ones
+            for _ in 0..3 {
+                assert_eq!(schedule.bucket(true).reserve(start), Duration::ZERO);
+            }
+            assert_eq!(
+                schedule.bucket(true).reserve(start),
+                EXISTING_PACKAGE_REFILL
+            );
+            assert_eq!(
+                schedule
+                    .bucket(true)
+                    .reserve(start + EXISTING_PACKAGE_REFILL),
+                EXISTING_PACKAGE_REFILL
+            );
+        }
+
+        #[test]
+        fn default_bursts() {
+            let start = Instant::now();
+            let rate_limit = crate::config::RateLimit::new();
+            let mut schedule = Schedule::new(&rate_limit, start);
+
+            for _ in 0..rate_limit.new_packages() {
+                assert_eq!(schedule.bucket(false).reserve(start), Duration::ZERO);
+            }
+            assert_eq!(schedule.bucket(false).reserve(start), NEW_PACKAGE_REFILL);
+            for _ in 0..rate_limit.existing_packages() {
+                assert_eq!(schedule.bucket(true).reserve(start), Duration::ZERO);
+            }
+            assert_eq!(
+                schedule.bucket(true).reserve(start),
+                EXISTING_PACKAGE_REFILL
+            );
+        }
+
+        #[test]
+        fn rate_limited_waits_for_retry() {
+            let start = Instant::now();
+            let mut schedule = Schedule::new(&crate::config::RateLimit::new(), start);
+
+            let bucket = schedule.bucket(false);
+            assert_eq!(bucket.reserve(start), Duration::ZERO);
+            // The registry didn't say when to retry
+            let retry_after = bucket.refill();
+            assert_eq!(retry_after, NEW_PACKAGE_REFILL);
+            bucket.pause(start, retry_after);
+            assert_eq!(bucket.reserve(start), NEW_PACKAGE_REFILL);
+        }
+    }
+
+    #[cfg(unix)]
+    mod publish_within_rate_limit {
+        use super::*;
+
+        use std::time::{Duration, Instant};
+
+        /// Publish with `publish` standing in for `cargo publish`, recording the waits
+        fn run(
+            bucket: &mut Bucket,
+            mut publish: impl FnMut(usize) -> String,
+        ) -> (Result<(), CliError>, usize, Vec<Duration>) {
+            let mut calls = 0;
+            let mut sleeps = Vec::new();
+            let result = publish_within_rate_limit(
+                "foo",
+                bucket,
+                false,
+                || {
+                    calls += 1;
+                    crate::ops::cmd::call_with_timeout(
+                        ["sh".to_owned(), "-c".to_owned(), publish(calls)],
+                        Default::default(),
+                        None,
+                        None,
+                        true,
+                    )
+                },
+                |wait| sleeps.push(wait),
+            );
+            (result, calls, sleeps)
+        }
+
+        /// What crates.io responds with once a crate is over the rate limit
+        fn rate_limited(retry_at: Option<time::OffsetDateTime>) -> String {
+            let mut msg =
+                "the remote server responded with an error (status 429 Too Many Requests): \
+                You have published too many new crates in a short period of time."
+                    .to_owned();
+            if let Some(retry_at) = retry_at {
+                let retry_at = retry_at
+                    .format(time::macros::format_description!(
+                        "[weekday repr:short], [day] [month repr:short] [year] [hour]:[minute]:[second] GMT"
+                    ))
+                    .unwrap();
+                msg.push_str(&format!(" Please try again after {retry_at}"));
+            }
+            format!("echo '{msg}' >&2; exit 101")
+        }
+
+        #[test]
+        fn retries_at_requested_time() {
+            let mut bucket = Bucket::new(2, NEW_PACKAGE_REFILL, Instant::now());
+            let retry_at = time::OffsetDateTime::now_utc() + Duration::from_secs(120);
+            let (result, calls, sleeps) = run(&mut bucket, |call| match call {
+                1 => rate_limited(Some(retry_at)),
+                _ => "exit 0".to_owned(),
+            });
+            result.unwrap();
+            assert_eq!(calls, 2);
+            // The rest of the burst is held back until the registry's retry time, not a full refill
+            assert_eq!(sleeps.len(), 1, "{sleeps:?}");
+            assert!(
+                Duration::from_secs(110) < sleeps[0] && sleeps[0] <= Duration::from_secs(120),
+                "{sleeps:?}"
+            );
+        }
+
+        #[test]
+        fn retries_after_refill() {
+            let mut bucket = Bucket::new(2, NEW_PACKAGE_REFILL, Instant::now());
+            let (result, calls, sleeps) = run(&mut bucket, |call| match call {
+                1 => rate_limited(None),
+                _ => "exit 0".to_owned(),
+            });
+            result.unwrap();
+            assert_eq!(calls, 2);
+            assert_eq!(sleeps.len(), 1, "{sleeps:?}");
+            assert!(
+                NEW_PACKAGE_REFILL - Duration::from_secs(10) < sleeps[0]
+                    && sleeps[0] <= NEW_PACKAGE_REFILL,
+                "{sleeps:?}"
+            );
+        }
+
+        #[test]
+        fn gives_up() {
+            let mut bucket = Bucket::new(2, NEW_PACKAGE_REFILL, Instant::now());
+            let (result, calls, sleeps) = run(&mut bucket, |_| rate_limited(None));
+            assert!(result.is_err());
+            assert_eq!(calls, MAX_RATE_LIMIT_RETRIES + 1);
+            assert_eq!(sleeps.len(), MAX_RATE_LIMIT_RETRIES, "{sleeps:?}");
+        }
+
+        #[test]
+        fn other_errors_are_not_retried() {
+            let mut bucket = Bucket::new(2, NEW_PACKAGE_REFILL, Instant::now());
+            let (result, calls, sleeps) = run(&mut bucket, |_| {
+                "echo 'error: 401 Unauthorized' >&2; exit 101".to_owned()
+            });
+            assert!(result.is_err());
+            assert_eq!(calls, 1);
+            assert!(sleeps.is_empty(), "{sleeps:?}");
+        }
+    }
+
+    mod publish {
+        use super::*;
+
+        use crate::steps::BumpLevel;
+        use crate::steps::fixture::Workspace;
+
+        #[test]
+        fn records_each_package() {
+            let ws = Workspace::new("", &[("foo", "0.1.0", &[]), ("bar", "0.1.0", &[])]);
+            let ws_meta = ws.metadata();
+            let mut pkgs = ws.plan(&ws_meta, BumpLevel::Patch);
+            pkgs[0].config.publish = Some(false);
+            // Publishing one at a time, the second one fails
+            pkgs[1].config.registry = Some("cargo-release-test-unknown-registry".to_owned());
+            pkgs[1].config.verify = Some(false);
+
+            let mut index = crate::ops::index::CratesIoIndex::new();
+            let mut published = Vec::new();
+            let result = publish(
+                &mut index,
+                &crate::config::RateLimit::new(),
+                &pkgs,
+                true,
+                |pkg| {
+                    published.push(pkg.meta.name.to_string());
+                    Ok(())
+                },
+            );
+            assert!(result.is_err());
+            assert_eq!(published, [pkgs[0].meta.name.to_string()]);
+        }
+    }
+
+    mod poll {
+        use super::*;
+
+        use std::cell::{Cell, RefCell};
+        use std::time::Duration;
+
+        /// Run `poll` against a fake clock that only advances while sleeping
+        fn run(
+            poll: &Poll,
+            check: impl FnMut() -> Result<Option<bool>, CliError>,
+        ) -> (Result<(), CliError>, Vec<Duration>) {
+            let clock = Cell::new(Duration::ZERO);
+            let sleeps = RefCell::new(Vec::new());
+            let result = poll.run(
+                "foo 1.0.0",
+                "crates.io",
+                check,
+                || clock.get(),
+                |wait| {
+                    clock.set(clock.get() + wait);
+                    sleeps.borrow_mut().push(wait);
+                },
+            );
+            (result, sleeps.into_inner())
+        }
+
+        #[test]
+        fn available_immediately() {
+            let poll = Poll {
+                timeout: Duration::from_secs(300),
+                interval: Duration::from_secs(1),
+            };
+            let (result, sleeps) = run(&poll, || Ok(Some(true)));
+            result.unwrap();
+            assert!(sleeps.is_empty());
+        }
+
+        #[test]
+        fn backs_off_until_available() {
+            let poll = Poll {
+                timeout: Duration::from_secs(300),
+                interval: Duration::from_secs(20),
+            };
+            let mut checks = 0;
+            let (result, sleeps) = run(&poll, || {
+                checks += 1;
+                // Missing crate, then a stale index without the new version
+                Ok(match checks {
+                    1 => None,
+                    2..=4 => Some(false),
+                    _ => Some(true),
+                })
+            });
+            result.unwrap();
+            assert_eq!(checks, 5);
+            assert_eq!(
+                sleeps,
+                [20, 40, 60, 60].map(Duration::from_secs),
+                "doubles up to the maximum interval"
+            );
+        }
+
+        #[test]
+        fn times_out() {
+            let poll = Poll {
+                timeout: Duration::from_secs(100),
+                interval: Duration::from_secs(30),
+            };
+            let (result, sleeps) = run(&poll, || Ok(Some(false)));
+            let err = result.unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "timed out after 100s waiting for foo 1.0.0 to be available on crates.io"
+            );
+            assert_eq!(
+                sleeps,
+                [30, 60, 10].map(Duration::from_secs),
+                "doesn't sleep past the timeout"
+            );
+        }
+
+        #[test]
+        fn times_out_with_last_error() {
+            let poll = Poll {
+                timeout: Duration::from_secs(10),
+                interval: Duration::from_secs(5),
+            };
+            let mut checks = 0;
+            let (result, _) = run(&poll, || {
+                checks += 1;
+                match checks {
+                    1 => Err(anyhow::format_err!("connection refused").into()),
+                    _ => Err(anyhow::format_err!("403 forbidden").into()),
+                }
+            });
+            let err = result.unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "timed out after 10s waiting for foo 1.0.0 to be available on crates.io: 403 forbidden"
+            );
+        }
     }
 }
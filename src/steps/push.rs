@@ -13,6 +13,9 @@ pub struct PushStep {
     #[command(flatten)]
     workspace: clap_cargo::Workspace,
 
+    #[command(flatten)]
+    group: super::GroupArgs,
+
     /// Custom config file
     #[arg(short, long = "config", value_name = "PATH")]
     custom_config: Option<std::path::PathBuf>,
@@ -64,9 +67,10 @@ impl PushStep {
             .exec()?;
         let config = self.to_config();
         let ws_config = crate::config::load_workspace_config(&config, &ws_meta)?;
+        let workspace = self.group.select(&self.workspace, &ws_config, &ws_meta)?;
         let mut pkgs = plan::load(&config, &ws_meta)?;
 
-        let (_selected_pkgs, excluded_pkgs) = self.workspace.partition_packages(&ws_meta);
+        let (_selected_pkgs, excluded_pkgs) = workspace.partition_packages(&ws_meta);
         for excluded_pkg in excluded_pkgs {
             let Some(pkg) = pkgs.get_mut(&excluded_pkg.id) else {
                 // Either not in workspace or marked as `release = false`.
@@ -147,7 +151,8 @@ pub fn push(
     ws_meta: &cargo_metadata::Metadata,
     pkgs: &[plan::PackageRelease],
     dry_run: bool,
-) -> Result<(), CliError> {
+) -> Result<Vec<String>, CliError> {
+    let mut pushed_refs = Vec::new();
     if ws_config.push() {
         let git_remote = ws_config.push_remote();
         let branch = git::current_branch(ws_meta.workspace_root.as_std_path())?;
@@ -177,6 +182,7 @@ pub fn push(
                 "Pushing",
                 format!("Pushing {} to {}", shared_refs.join(", "), git_remote),
             );
+            pushed_refs.extend(shared_refs.iter().map(|r| (*r).to_owned()));
             if !git::push(
                 ws_meta.workspace_root.as_std_path(),
                 git_remote,
@@ -189,5 +195,5 @@ pub fn push(
         }
     }
 
-    Ok(())
+    Ok(pushed_refs)
 }
@@ -1,7 +1,11 @@
+use std::io::Write as _;
+
 use crate::config;
 use crate::error::CliError;
 use crate::ops::cargo;
 use crate::ops::git;
+use crate::steps::hook::HookPoint;
+use crate::steps::journal::{Action, Journal};
 use crate::steps::plan;
 
 #[derive(Debug, Clone, clap::Args)]
@@ -12,11 +16,20 @@ pub struct ReleaseStep {
     #[command(flatten)]
     workspace: clap_cargo::Workspace,
 
+    #[command(flatten)]
+    group: super::GroupArgs,
+
     /// Process all packages whose current version is unpublished
     #[arg(long, conflicts_with = "level_or_version")]
     unpublished: bool,
 
-    /// Either bump by LEVEL or set the VERSION for all selected packages
+    /// Process packages changed since their last release, along with dependents whose requirement
+    /// on them must move
+    #[arg(long, conflicts_with = "unpublished")]
+    changed: bool,
+
+    /// Either bump by LEVEL, including any of the `pre-release-channels` like `beta`, or set the
+    /// VERSION for all selected packages
     #[arg(value_name = "LEVEL|VERSION")]
     level_or_version: Option<super::TargetVersion>,
 
@@ -39,6 +52,18 @@ pub struct ReleaseStep {
     #[arg(long, value_name = "NAME")]
     prev_tag_name: Option<String>,
 
+    /// Continue a failed release from the last completed action
+    #[arg(long, conflicts_with_all = ["level_or_version", "unpublished", "changed", "group", "prev_tag_name"])]
+    resume: bool,
+
+    /// Undo the local release commit and tags if the release fails before anything is published
+    #[arg(long, conflicts_with = "resume")]
+    rollback_on_failure: bool,
+
+    /// Print the release plan and the result of each check instead of releasing
+    #[arg(long, value_enum, default_value_t, conflicts_with = "execute")]
+    output_format: super::OutputFormat,
+
     #[command(flatten)]
     config: config::ConfigArgs,
 }
@@ -53,6 +78,10 @@ impl ReleaseStep {
                 crate::ops::shell::warn("`--dry-run` is superfluous, dry-run is done by default");
         }
 
+        if self.resume {
+            return self.resume(&mut index);
+        }
+
         let ws_meta = self
             .manifest
             .metadata()
@@ -60,7 +89,11 @@ impl ReleaseStep {
             .features(cargo_metadata::CargoOpt::AllFeatures)
             .exec()?;
         let mut ws_config = config::load_workspace_config(&self.config, &ws_meta)?;
+        let workspace = self.group.select(&self.workspace, &ws_config, &ws_meta)?;
         let mut pkgs = plan::load(&self.config, &ws_meta)?;
+        if let Some(level_or_version) = &self.level_or_version {
+            super::validate_target(level_or_version, &pkgs)?;
+        }
 
         for pkg in pkgs.values_mut() {
             if let Some(prev_tag) = self.prev_tag_name.as_ref() {
@@ -71,24 +104,27 @@ impl ReleaseStep {
             if pkg.config.release()
                 && let Some(level_or_version) = &self.level_or_version
             {
-                pkg.bump(level_or_version, self.metadata.as_deref())?;
+                pkg.bump(&ws_meta, level_or_version, self.metadata.as_deref())?;
             }
-            if index.has_krate(
+            if cargo::crate_exists(
+                &mut index,
                 pkg.config.registry(),
                 &pkg.meta.name,
                 pkg.config.certs_source(),
-            )? {
+            ) {
                 // Already published, skip it.  Use `cargo release owner` for one-time updates
                 pkg.ensure_owners = false;
             }
         }
 
-        let (_selected_pkgs, excluded_pkgs) =
-            if self.unpublished && self.workspace == clap_cargo::Workspace::default() {
-                ws_meta.packages.iter().partition(|_| false)
-            } else {
-                self.workspace.partition_packages(&ws_meta)
-            };
+        let excluded_pkgs = super::deselected_packages(
+            &ws_meta,
+            &workspace,
+            &pkgs,
+            self.changed,
+            self.unpublished && workspace == clap_cargo::Workspace::default(),
+        );
+        let mut deselected = std::collections::HashSet::new();
         for excluded_pkg in &excluded_pkgs {
             let Some(pkg) = pkgs.get_mut(&excluded_pkg.id) else {
                 // Either not in workspace or marked as `release = false`.
@@ -99,7 +135,7 @@ impl ReleaseStep {
             }
 
             let crate_name = pkg.meta.name.as_str();
-            let explicitly_excluded = self.workspace.exclude.contains(&excluded_pkg.name);
+            let explicitly_excluded = workspace.exclude.contains(&excluded_pkg.name);
             // 1. Don't show this message if already not releasing in config
             // 2. Still respect `--exclude`
             if pkg.config.release()
@@ -126,6 +162,9 @@ impl ReleaseStep {
 
             pkg.planned_version = None;
             pkg.config.release = Some(false);
+            if !explicitly_excluded {
+                deselected.insert(pkg.meta.id.clone());
+            }
 
             if let Some(prior_tag_name) = &pkg.prior_tag {
                 if let Some(changed) =
@@ -150,7 +189,7 @@ impl ReleaseStep {
             }
         }
 
-        let pkgs = plan::plan(pkgs)?;
+        let pkgs = plan::plan_with_dependents(pkgs, &deselected)?;
 
         for excluded_pkg in &excluded_pkgs {
             let Some(pkg) = pkgs.get(&excluded_pkg.id) else {
@@ -158,8 +197,7 @@ impl ReleaseStep {
                 continue;
             };
 
-            // HACK: `index` only supports default registry
-            if pkg.config.publish() && pkg.config.registry().is_none() {
+            if pkg.config.publish() {
                 let version = pkg.planned_version.as_ref().unwrap_or(&pkg.initial_version);
                 let crate_name = pkg.meta.name.as_str();
                 if !cargo::is_published(
@@ -188,21 +226,41 @@ impl ReleaseStep {
 
         let dry_run = !self.execute;
         let mut failed = false;
+        let mut checks = plan::Checks::default();
 
         let consolidate_commits = super::consolidate_commits(&selected_pkgs, &excluded_pkgs)?;
         ws_config.consolidate_commits = Some(consolidate_commits);
 
         // STEP 0: Help the user make the right decisions.
-        failed |= !super::verify_git_is_clean(
-            ws_meta.workspace_root.as_std_path(),
-            dry_run,
-            log::Level::Error,
-        )?;
+        failed |= !checks.record(
+            "git-is-clean",
+            super::verify_git_is_clean(
+                ws_meta.workspace_root.as_std_path(),
+                dry_run,
+                log::Level::Error,
+            )?,
+        );
 
-        failed |= !super::verify_tags_missing(&selected_pkgs, dry_run, log::Level::Error)?;
+        failed |= !checks.record(
+            "tags-missing",
+            super::verify_tags_missing(&selected_pkgs, dry_run, log::Level::Error)?,
+        );
 
-        failed |=
-            !super::verify_monotonically_increasing(&selected_pkgs, dry_run, log::Level::Error)?;
+        failed |= !checks.record(
+            "monotonically-increasing",
+            super::verify_monotonically_increasing(&selected_pkgs, dry_run, log::Level::Error)?,
+        );
+
+        failed |= !checks.record(
+            "semver",
+            super::verify_semver_compatible(
+                &ws_meta,
+                &selected_pkgs,
+                &mut index,
+                dry_run,
+                log::Level::Error,
+            )?,
+        );
 
         let mut double_publish = false;
         for pkg in &selected_pkgs {
@@ -226,7 +284,7 @@ impl ReleaseStep {
                 double_publish = true;
             }
         }
-        if double_publish {
+        if !checks.record("double-publish", !double_publish) {
             failed = true;
             if !dry_run {
                 return Err(101.into());
@@ -235,36 +293,261 @@ impl ReleaseStep {
 
         super::warn_changed(&ws_meta, &selected_pkgs)?;
 
-        failed |= !super::verify_git_branch(
-            ws_meta.workspace_root.as_std_path(),
+        failed |= !checks.record(
+            "git-branch",
+            super::verify_git_branch(
+                ws_meta.workspace_root.as_std_path(),
+                &ws_config,
+                dry_run,
+                log::Level::Error,
+            )?,
+        );
+
+        failed |= !checks.record(
+            "if-behind",
+            super::verify_if_behind(
+                ws_meta.workspace_root.as_std_path(),
+                &ws_config,
+                dry_run,
+                log::Level::Warn,
+            )?,
+        );
+
+        failed |= !checks.record(
+            "metadata",
+            super::verify_metadata(&selected_pkgs, dry_run, log::Level::Error)?,
+        );
+        failed |= !checks.record(
+            "rate-limit",
+            super::verify_rate_limit(
+                &selected_pkgs,
+                &mut index,
+                &ws_config.rate_limit,
+                dry_run,
+                log::Level::Error,
+            )?,
+        );
+
+        if self.output_format == super::OutputFormat::Json {
+            let report = plan::Report::new(&selected_pkgs, &excluded_pkgs, &checks);
+            let mut output = report.to_json()?;
+            output.push('\n');
+            std::io::stdout().write_all(output.as_bytes())?;
+            return if failed { Err(101.into()) } else { Ok(()) };
+        }
+
+        // STEP 1: Release Confirmation
+        super::confirm("Release", &selected_pkgs, self.no_confirm, dry_run)?;
+        let plan_file =
+            plan::Report::new(&selected_pkgs, &excluded_pkgs, &checks).persist(&ws_meta)?;
+
+        let journal_path = Journal::path(&ws_meta);
+        if !dry_run && journal_path.exists() {
+            let _ = crate::ops::shell::warn(format!(
+                "discarding incomplete release recorded in {}; use `--resume` to continue it instead",
+                journal_path.display()
+            ));
+        }
+        let mut journal = Journal::new(&journal_path, &selected_pkgs, !dry_run)?;
+
+        let head_id = git::head_id(ws_meta.workspace_root.as_std_path())?;
+        if let Err(err) = release(
+            &ws_meta,
             &ws_config,
+            &selected_pkgs,
+            &excluded_pkgs,
+            &mut journal,
+            &mut index,
+            plan_file.path(),
+            dry_run,
+        ) {
+            if self.rollback_on_failure {
+                rollback(
+                    &ws_meta,
+                    &selected_pkgs,
+                    journal,
+                    &mut index,
+                    &head_id,
+                    dry_run,
+                )?;
+            }
+            return Err(err);
+        }
+        journal.remove()?;
+
+        super::finish(failed, dry_run)
+    }
+
+    fn resume(&self, index: &mut crate::ops::index::CratesIoIndex) -> Result<(), CliError> {
+        let ws_meta = self
+            .manifest
+            .metadata()
+            // When evaluating dependency ordering, we need to consider optional dependencies
+            .features(cargo_metadata::CargoOpt::AllFeatures)
+            .exec()?;
+        let mut ws_config = config::load_workspace_config(&self.config, &ws_meta)?;
+        let mut pkgs = plan::load(&self.config, &ws_meta)?;
+
+        let dry_run = !self.execute;
+        let journal_path = Journal::path(&ws_meta);
+        let mut journal = Journal::load(&journal_path, !dry_run)?;
+
+        // Restore the plan from before the release commit changed the manifests
+        for pkg in pkgs.values_mut() {
+            if let Some(entry) = journal.package(pkg.meta.name.as_str()) {
+                pkg.initial_version = plan::Version::from(
+                    semver::Version::parse(&entry.initial_version).map_err(anyhow::Error::from)?,
+                );
+                pkg.planned_version = entry
+                    .planned_version
+                    .as_deref()
+                    .map(semver::Version::parse)
+                    .transpose()
+                    .map_err(anyhow::Error::from)?
+                    .map(plan::Version::from);
+            } else {
+                pkg.planned_version = None;
+                pkg.config.release = Some(false);
+            }
+        }
+
+        let pkgs = plan::plan(pkgs)?;
+
+        let (selected_pkgs, excluded_pkgs): (Vec<_>, Vec<_>) = pkgs
+            .into_iter()
+            .map(|(_, pkg)| pkg)
+            .partition(|p| p.config.release());
+        if selected_pkgs.is_empty() {
+            let _ = crate::ops::shell::error(format!(
+                "no packages in {} to resume",
+                journal_path.display()
+            ));
+            return Err(2.into());
+        }
+        for pkg in &selected_pkgs {
+            let entry = journal
+                .package(pkg.meta.name.as_str())
+                .expect("only journaled packages are selected");
+            if pkg.planned_tag != entry.planned_tag {
+                let _ = crate::ops::shell::error(format!(
+                    "planned tag for {} has changed since the release started ({} -> {})",
+                    pkg.meta.name,
+                    entry.planned_tag.as_deref().unwrap_or("none"),
+                    pkg.planned_tag.as_deref().unwrap_or("none"),
+                ));
+                return Err(101.into());
+            }
+        }
+
+        let mut failed = false;
+
+        let consolidate_commits = super::consolidate_commits(&selected_pkgs, &excluded_pkgs)?;
+        ws_config.consolidate_commits = Some(consolidate_commits);
+
+        // STEP 0: Help the user make the right decisions.
+        failed |= !super::verify_git_is_clean(
+            ws_meta.workspace_root.as_std_path(),
             dry_run,
             log::Level::Error,
         )?;
 
-        failed |= !super::verify_if_behind(
+        failed |= !super::verify_git_branch(
             ws_meta.workspace_root.as_std_path(),
             &ws_config,
             dry_run,
-            log::Level::Warn,
+            log::Level::Error,
         )?;
 
-        failed |= !super::verify_metadata(&selected_pkgs, dry_run, log::Level::Error)?;
-        failed |= !super::verify_rate_limit(
+        // Catch up on actions that completed without being recorded
+        for pkg in &selected_pkgs {
+            let crate_name = pkg.meta.name.as_str();
+            let version = pkg.planned_version.as_ref().unwrap_or(&pkg.initial_version);
+            if !journal.is_complete(pkg, Action::Commit)
+                && pkg.planned_version.is_some()
+                && pkg.meta.version == version.full_version
+            {
+                log::debug!("{crate_name} is already at {}", version.full_version_string);
+                journal.complete(std::slice::from_ref(pkg), Action::Commit)?;
+            }
+            if !journal.is_complete(pkg, Action::PostRelease)
+                && let Some(post_version) = super::post_release::post_release_version(pkg)?
+                && pkg.meta.version == post_version.full_version
+            {
+                log::debug!(
+                    "{crate_name} is already at {}",
+                    post_version.full_version_string
+                );
+                journal.complete(std::slice::from_ref(pkg), Action::Commit)?;
+                journal.complete(std::slice::from_ref(pkg), Action::PostRelease)?;
+            }
+            if !journal.is_complete(pkg, Action::Publish)
+                && pkg.config.publish()
+                && cargo::is_published(
+                    index,
+                    pkg.config.registry(),
+                    crate_name,
+                    &version.full_version_string,
+                    pkg.config.certs_source(),
+                )
+            {
+                log::debug!(
+                    "{crate_name} {} is already published",
+                    version.full_version_string
+                );
+                journal.complete(std::slice::from_ref(pkg), Action::Publish)?;
+            }
+            if !journal.is_complete(pkg, Action::Tag)
+                && let Some(tag_name) = pkg.planned_tag.as_deref()
+                && git::tag_exists(&pkg.package_root, tag_name)?
+            {
+                log::debug!("tag `{tag_name}` already exists");
+                journal.complete(std::slice::from_ref(pkg), Action::Tag)?;
+            }
+        }
+
+        // STEP 1: Release Confirmation
+        super::confirm("Resume", &selected_pkgs, self.no_confirm, dry_run)?;
+        let plan_file = plan::Report::new(&selected_pkgs, &excluded_pkgs, &plan::Checks::default())
+            .persist(&ws_meta)?;
+
+        release(
+            &ws_meta,
+            &ws_config,
             &selected_pkgs,
-            &mut index,
-            &ws_config.rate_limit,
+            &excluded_pkgs,
+            &mut journal,
+            index,
+            plan_file.path(),
             dry_run,
-            log::Level::Error,
         )?;
+        journal.remove()?;
 
-        // STEP 1: Release Confirmation
-        super::confirm("Release", &selected_pkgs, self.no_confirm, dry_run)?;
+        super::finish(failed, dry_run)
+    }
+}
 
-        // STEP 2: update current version, save and commit
-        if consolidate_commits {
+/// Perform the release actions not yet recorded in `journal`
+#[allow(
+    clippy::too_many_arguments,
+    reason = "the release threads the state of the whole run"
+)]
+fn release(
+    ws_meta: &cargo_metadata::Metadata,
+    ws_config: &config::Config,
+    selected_pkgs: &[plan::PackageRelease],
+    excluded_pkgs: &[plan::PackageRelease],
+    journal: &mut Journal,
+    index: &mut crate::ops::index::CratesIoIndex,
+    plan_path: &std::path::Path,
+    dry_run: bool,
+) -> Result<(), CliError> {
+    // STEP 2: update current version, save and commit
+    if ws_config.consolidate_commits() {
+        if journal.pending(selected_pkgs, Action::Commit).is_empty() {
+            log::debug!("skipping release commit, already done");
+        } else {
             let update_lock =
-                super::version::update_versions(&ws_meta, &selected_pkgs, &excluded_pkgs, dry_run)?;
+                super::version::update_versions(ws_meta, selected_pkgs, excluded_pkgs, dry_run)?;
             if update_lock {
                 log::debug!("updating lock file");
                 if !dry_run {
@@ -273,61 +556,503 @@ impl ReleaseStep {
                 }
             }
 
-            for pkg in &selected_pkgs {
+            for pkg in selected_pkgs {
                 super::replace::replace(pkg, dry_run)?;
+                super::changes::changelog(ws_meta, pkg, dry_run)?;
 
                 // pre-release hook
-                super::hook::hook(&ws_meta, pkg, dry_run)?;
+                super::hook::hook(ws_meta, plan_path, pkg, dry_run)?;
             }
+            super::hook::workspace_pre_release_hook(
+                ws_meta,
+                plan_path,
+                ws_config,
+                selected_pkgs,
+                dry_run,
+            )?;
 
-            super::commit::workspace_commit(&ws_meta, &ws_config, &selected_pkgs, dry_run)?;
-        } else {
-            for pkg in &selected_pkgs {
-                if let Some(version) = pkg.planned_version.as_ref() {
-                    let crate_name = pkg.meta.name.as_str();
-                    let _ = crate::ops::shell::status(
-                        "Upgrading",
-                        format!(
-                            "{} from {} to {}",
-                            crate_name,
-                            pkg.initial_version.full_version_string,
-                            version.full_version_string
-                        ),
-                    );
-                    cargo::set_package_version(
-                        &pkg.manifest_path,
-                        version.full_version_string.as_str(),
-                        dry_run,
-                    )?;
-                    crate::steps::version::update_dependent_versions(
-                        &ws_meta, pkg, version, dry_run,
-                    )?;
-                    if dry_run {
-                        log::debug!("updating lock file");
-                    } else {
-                        cargo::update_lock(&pkg.manifest_path)?;
-                    }
+            super::commit::workspace_commit(ws_meta, ws_config, selected_pkgs, dry_run)?;
+            journal.complete(selected_pkgs, Action::Commit)?;
+
+            let commit_id = git::head_id(ws_meta.workspace_root.as_std_path())?;
+            super::hook::lifecycle_hook(
+                ws_meta,
+                plan_path,
+                ws_config,
+                selected_pkgs,
+                HookPoint::PostCommit,
+                |_| vec![("COMMIT_ID", commit_id.clone())],
+                dry_run,
+            )?;
+        }
+    } else {
+        if ws_config.workspace_hook(HookPoint::PreRelease).is_some() {
+            let _ = crate::ops::shell::warn(
+                "ignoring `workspace-pre-release-hook`; it requires `consolidate-commits`",
+            );
+        }
+        let mut committed = Vec::new();
+        let mut commit_ids = std::collections::HashMap::new();
+        for pkg in selected_pkgs {
+            if journal.is_complete(pkg, Action::Commit) {
+                log::debug!(
+                    "skipping release commit for {}, already done",
+                    pkg.meta.name
+                );
+                continue;
+            }
+
+            if let Some(version) = pkg.planned_version.as_ref() {
+                let crate_name = pkg.meta.name.as_str();
+                let _ = crate::ops::shell::status(
+                    "Upgrading",
+                    format!(
+                        "{} from {} to {}",
+                        crate_name,
+                        pkg.initial_version.full_version_string,
+                        version.full_version_string
+                    ),
+                );
+                cargo::set_package_version(
+                    &pkg.manifest_path,
+                    version.full_version_string.as_str(),
+                    dry_run,
+                )?;
+                crate::steps::version::update_dependent_versions(ws_meta, pkg, version, dry_run)?;
+                if dry_run {
+                    log::debug!("updating lock file");
+                } else {
+                    cargo::update_lock(&pkg.manifest_path)?;
                 }
+            }
 
-                super::replace::replace(pkg, dry_run)?;
+            super::replace::replace(pkg, dry_run)?;
+            super::changes::changelog(ws_meta, pkg, dry_run)?;
 
-                // pre-release hook
-                super::hook::hook(&ws_meta, pkg, dry_run)?;
+            // pre-release hook
+            super::hook::hook(ws_meta, plan_path, pkg, dry_run)?;
+
+            super::commit::pkg_commit(pkg, dry_run)?;
+            journal.complete(std::slice::from_ref(pkg), Action::Commit)?;
+
+            let commit_id = git::head_id(ws_meta.workspace_root.as_std_path())?;
+            commit_ids.insert(pkg.meta.id.clone(), commit_id);
+            committed.push(pkg.clone());
+        }
+
+        let head_id = git::head_id(ws_meta.workspace_root.as_std_path())?;
+        super::hook::lifecycle_hook(
+            ws_meta,
+            plan_path,
+            ws_config,
+            &committed,
+            HookPoint::PostCommit,
+            |pkg| {
+                let commit_id = pkg
+                    .and_then(|pkg| commit_ids.get(&pkg.meta.id))
+                    .unwrap_or(&head_id);
+                vec![("COMMIT_ID", commit_id.clone())]
+            },
+            dry_run,
+        )?;
+    }
+
+    // STEP 3: cargo publish
+    let pending = journal.pending(selected_pkgs, Action::Publish);
+    let publishing = pending
+        .iter()
+        .filter(|pkg| pkg.config.publish())
+        .cloned()
+        .collect::<Vec<_>>();
+    let registry_envs = |pkg: Option<&plan::PackageRelease>| registry_envs(ws_config, pkg);
+    super::hook::lifecycle_hook(
+        ws_meta,
+        plan_path,
+        ws_config,
+        &publishing,
+        HookPoint::PrePublish,
+        registry_envs,
+        dry_run,
+    )?;
+    // Record each package as it goes so `--resume` doesn't publish it again
+    super::publish::publish(index, &ws_config.rate_limit, &pending, dry_run, |pkg| {
+        journal.complete(std::slice::from_ref(pkg), Action::Publish)
+    })?;
+    super::hook::lifecycle_hook(
+        ws_meta,
+        plan_path,
+        ws_config,
+        &publishing,
+        HookPoint::PostPublish,
+        registry_envs,
+        dry_run,
+    )?;
+    let pending = journal.pending(selected_pkgs, Action::Owner);
+    super::owner::ensure_owners(&pending, dry_run)?;
+    journal.complete(&pending, Action::Owner)?;
+
+    // STEP 5: Tag
+    let pending = journal.pending(selected_pkgs, Action::Tag);
+    super::tag::tag(&pending, dry_run)?;
+    journal.complete(&pending, Action::Tag)?;
+    let tagged = pending
+        .iter()
+        .filter(|pkg| pkg.planned_tag.is_some())
+        .cloned()
+        .collect::<Vec<_>>();
+    let mut tag_names = tagged
+        .iter()
+        .filter_map(|pkg| pkg.planned_tag.clone())
+        .collect::<Vec<_>>();
+    tag_names.sort_unstable();
+    tag_names.dedup();
+    super::hook::lifecycle_hook(
+        ws_meta,
+        plan_path,
+        ws_config,
+        &tagged,
+        HookPoint::PostTag,
+        |pkg| tag_envs(&tag_names, pkg),
+        dry_run,
+    )?;
+
+    // STEP 6: Bump to the next development version
+    let pending = journal.pending(selected_pkgs, Action::PostRelease);
+    super::post_release::post_release(ws_meta, ws_config, &pending, excluded_pkgs, dry_run)?;
+    journal.complete(&pending, Action::PostRelease)?;
+
+    // STEP 7: git push
+    let pending = journal.pending(selected_pkgs, Action::Push);
+    let pushed_refs = super::push::push(ws_config, ws_meta, &pending, dry_run)?;
+    journal.complete(&pending, Action::Push)?;
+    if !pushed_refs.is_empty() {
+        let pushed = pending
+            .iter()
+            .filter(|pkg| pkg.config.push())
+            .cloned()
+            .collect::<Vec<_>>();
+        super::hook::lifecycle_hook(
+            ws_meta,
+            plan_path,
+            ws_config,
+            &pushed,
+            HookPoint::PostPush,
+            |_| {
+                vec![
+                    ("PUSH_REMOTE", ws_config.push_remote().to_owned()),
+                    ("PUSHED_REFS", pushed_refs.join(" ")),
+                ]
+            },
+            dry_run,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// The `REGISTRY` publish hooks run for, the workspace's when `pkg` is `None`
+fn registry_envs(
+    ws_config: &config::Config,
+    pkg: Option<&plan::PackageRelease>,
+) -> Vec<(&'static str, String)> {
+    let registry = pkg
+        .map(|pkg| &pkg.config)
+        .unwrap_or(ws_config)
+        .registry()
+        .unwrap_or("crates-io");
+    vec![("REGISTRY", registry.to_owned())]
+}
+
+/// The `TAG_NAME` of `pkg` or, for the workspace, all of `tag_names`
+fn tag_envs(
+    tag_names: &[String],
+    pkg: Option<&plan::PackageRelease>,
+) -> Vec<(&'static str, String)> {
+    match pkg {
+        Some(pkg) => vec![("TAG_NAME", pkg.planned_tag.clone().unwrap_or_default())],
+        None => vec![("TAG_NAMES", tag_names.join(" "))],
+    }
+}
+
+/// Undo the local release commit and tags, unless anything has left this machine
+///
+/// Once a package is published, only the tags are removed so `--resume` can create them again.
+fn rollback(
+    ws_meta: &cargo_metadata::Metadata,
+    selected_pkgs: &[plan::PackageRelease],
+    journal: Journal,
+    index: &mut crate::ops::index::CratesIoIndex,
+    head_id: &str,
+    dry_run: bool,
+) -> Result<(), CliError> {
+    let Some(failed_at) = journal.first_pending(selected_pkgs) else {
+        return Ok(());
+    };
+    if Action::Tag < failed_at {
+        let _ = crate::ops::shell::note(format!(
+            "not rolling back as the release failed while {}, after tagging; fix the failure and run `cargo release --resume`",
+            failed_at.doing()
+        ));
+        return Ok(());
+    }
 
-                super::commit::pkg_commit(pkg, dry_run)?;
+    let published = selected_pkgs
+        .iter()
+        .filter(|pkg| {
+            let version = pkg.planned_version.as_ref().unwrap_or(&pkg.initial_version);
+            pkg.config.publish()
+                && (journal.is_complete(pkg, Action::Publish)
+                    || cargo::is_published(
+                        index,
+                        pkg.config.registry(),
+                        pkg.meta.name.as_str(),
+                        &version.full_version_string,
+                        pkg.config.certs_source(),
+                    ))
+        })
+        .map(|pkg| pkg.meta.name.as_str())
+        .collect::<Vec<_>>();
+
+    if published.is_empty() {
+        let _ = crate::ops::shell::status("Rolling back", format!("to {head_id}"));
+    } else {
+        let _ = crate::ops::shell::status("Rolling back", "tags");
+    }
+    // `verify_tags_missing` ensured none of the planned tags existed before the release
+    let mut seen_tags = std::collections::HashSet::new();
+    for pkg in selected_pkgs {
+        if let Some(tag_name) = pkg.planned_tag.as_deref()
+            && seen_tags.insert(tag_name)
+            && git::tag_exists(&pkg.package_root, tag_name)?
+        {
+            log::debug!("deleting git tag {tag_name}");
+            if !git::delete_tag(&pkg.package_root, tag_name, dry_run)? {
+                return Err(101.into());
             }
         }
+    }
+
+    if !published.is_empty() {
+        let _ = crate::ops::shell::note(format!(
+            "not rolling back the release commit as {} already published; fix the failure and run `cargo release --resume`",
+            published.join(", ")
+        ));
+        return Ok(());
+    }
+    if !git::reset_hard(ws_meta.workspace_root.as_std_path(), head_id, dry_run)? {
+        return Err(101.into());
+    }
+    journal.remove()?;
+
+    Ok(())
+}
 
-        // STEP 3: cargo publish
-        super::publish::publish(&selected_pkgs, dry_run)?;
-        super::owner::ensure_owners(&selected_pkgs, dry_run)?;
+#[cfg(test)]
+mod test {
+    use super::*;
 
-        // STEP 5: Tag
-        super::tag::tag(&selected_pkgs, dry_run)?;
+    #[cfg(unix)]
+    mod lifecycle_hooks {
+        use super::*;
 
-        // STEP 6: git push
-        super::push::push(&ws_config, &ws_meta, &selected_pkgs, dry_run)?;
+        use crate::steps::BumpLevel;
+        use crate::steps::fixture::Workspace;
 
-        super::finish(failed, dry_run)
+        fn log_hook(key: &str, line: &str) -> String {
+            format!("{key} = [\"sh\", \"-c\", 'echo \"{line}\" >> \"$WORKSPACE_ROOT/hooks\"']\n")
+        }
+
+        #[test]
+        fn order_and_envs() {
+            let mut config = "publish = false\nconsolidate-commits = false\n".to_owned();
+            for (key, line) in [
+                ("post-commit-hook", "post-commit $CRATE_NAME $COMMIT_ID"),
+                (
+                    "workspace-post-commit-hook",
+                    "workspace-post-commit $COMMIT_ID",
+                ),
+                ("post-tag-hook", "post-tag $CRATE_NAME $TAG_NAME"),
+                ("workspace-post-tag-hook", "workspace-post-tag $TAG_NAMES"),
+                ("post-push-hook", "post-push $CRATE_NAME $PUSHED_REFS"),
+                (
+                    "workspace-post-push-hook",
+                    "workspace-post-push $PUSHED_REFS",
+                ),
+            ] {
+                config.push_str(&log_hook(key, line));
+            }
+            let ws = Workspace::new(&config, &[("foo", "0.1.0", &[]), ("bar", "0.1.0", &[])]);
+            let ws_meta = ws.metadata();
+            let ws_config = config::load_workspace_config(&ws.config_args(), &ws_meta).unwrap();
+            let pkgs = ws.plan(&ws_meta, BumpLevel::Patch);
+            let plan_file = plan::Report::new(&pkgs, &[], &plan::Checks::default())
+                .persist(&ws_meta)
+                .unwrap();
+            let mut journal = Journal::new(&ws.root().join("state.json"), &pkgs, false).unwrap();
+            let mut index = crate::ops::index::CratesIoIndex::new();
+
+            release(
+                &ws_meta,
+                &ws_config,
+                &pkgs,
+                &[],
+                &mut journal,
+                &mut index,
+                plan_file.path(),
+                true,
+            )
+            .unwrap();
+
+            let head_id = ws.git(&["rev-parse", "HEAD"]).trim().to_owned();
+            let names = pkgs
+                .iter()
+                .map(|pkg| pkg.meta.name.as_str())
+                .collect::<Vec<_>>();
+            let refs = "bar-v0.1.1 foo-v0.1.1 main";
+            let mut expected = Vec::new();
+            for name in &names {
+                expected.push(format!("post-commit {name} {head_id}"));
+            }
+            expected.push(format!("workspace-post-commit {head_id}"));
+            for name in &names {
+                expected.push(format!("post-tag {name} {name}-v0.1.1"));
+            }
+            expected.push("workspace-post-tag bar-v0.1.1 foo-v0.1.1".to_owned());
+            for name in &names {
+                expected.push(format!("post-push {name} {refs}"));
+            }
+            expected.push(format!("workspace-post-push {refs}"));
+            assert_eq!(ws.read("hooks").lines().collect::<Vec<_>>(), expected);
+        }
+
+        #[test]
+        fn registry() {
+            let mut config = log_hook("pre-publish-hook", "pre-publish $CRATE_NAME $REGISTRY");
+            config.push_str(&log_hook(
+                "workspace-pre-publish-hook",
+                "workspace-pre-publish $REGISTRY",
+            ));
+            let ws = Workspace::new(&config, &[("foo", "0.1.0", &[]), ("bar", "0.1.0", &[])]);
+            let ws_meta = ws.metadata();
+            let ws_config = config::load_workspace_config(&ws.config_args(), &ws_meta).unwrap();
+            let mut pkgs = ws.plan(&ws_meta, BumpLevel::Patch);
+            for pkg in &mut pkgs {
+                if pkg.meta.name.as_str() == "bar" {
+                    pkg.config.registry = Some("alt".to_owned());
+                }
+            }
+            let plan_file = plan::Report::new(&pkgs, &[], &plan::Checks::default())
+                .persist(&ws_meta)
+                .unwrap();
+
+            crate::steps::hook::lifecycle_hook(
+                &ws_meta,
+                plan_file.path(),
+                &ws_config,
+                &pkgs,
+                HookPoint::PrePublish,
+                |pkg| registry_envs(&ws_config, pkg),
+                false,
+            )
+            .unwrap();
+
+            let mut lines = ws
+                .read("hooks")
+                .lines()
+                .map(str::to_owned)
+                .collect::<Vec<_>>();
+            assert_eq!(
+                lines.pop().as_deref(),
+                Some("workspace-pre-publish crates-io")
+            );
+            lines.sort_unstable();
+            assert_eq!(lines, ["pre-publish bar alt", "pre-publish foo crates-io"]);
+        }
+    }
+
+    mod rollback {
+        use super::*;
+
+        use crate::steps::BumpLevel;
+        use crate::steps::fixture::Workspace;
+
+        fn head(ws: &Workspace) -> String {
+            ws.git(&["rev-parse", "HEAD"]).trim().to_owned()
+        }
+
+        fn tags(ws: &Workspace) -> String {
+            ws.git(&["tag", "--list"])
+        }
+
+        /// Release `foo` and `bar` up to (but not including) `failed_at`
+        fn release(ws: &Workspace, release: &str, failed_at: Action) -> (String, Journal) {
+            let ws_meta = ws.metadata();
+            let pkgs = ws.plan(&ws_meta, BumpLevel::Patch);
+            let head_id = head(ws);
+            let mut journal = Journal::new(&ws.root().join("state.json"), &pkgs, false).unwrap();
+            for action in Action::ALL.into_iter().filter(|a| *a < failed_at) {
+                match action {
+                    Action::Commit => ws.commit(release),
+                    Action::Tag => {
+                        ws.tag("foo-v0.1.1");
+                        ws.tag("bar-v0.1.1");
+                    }
+                    _ => {}
+                }
+                journal.complete(&pkgs, action).unwrap();
+            }
+            if failed_at == Action::Tag {
+                // Failed part way through tagging
+                ws.tag("foo-v0.1.1");
+            }
+            (head_id, journal)
+        }
+
+        fn rollback(ws: &Workspace, head_id: &str, journal: Journal) {
+            let ws_meta = ws.metadata();
+            let pkgs = ws.plan(&ws_meta, BumpLevel::Patch);
+            let mut index = crate::ops::index::CratesIoIndex::new();
+            super::rollback(&ws_meta, &pkgs, journal, &mut index, head_id, false).unwrap();
+        }
+
+        fn workspace(release: &str) -> Workspace {
+            Workspace::new(
+                release,
+                &[("foo", "0.1.0", &[]), ("bar", "0.1.0", &[("foo", "0.1.0")])],
+            )
+        }
+
+        #[test]
+        fn commit_and_tags() {
+            let ws = workspace("publish = false");
+            let (head_id, journal) = release(&ws, "chore: Release", Action::Tag);
+            assert_ne!(head(&ws), head_id);
+
+            rollback(&ws, &head_id, journal);
+            assert_eq!(head(&ws), head_id);
+            assert_eq!(tags(&ws), "");
+        }
+
+        #[test]
+        fn published_keeps_commit() {
+            let ws = workspace("");
+            let (head_id, journal) = release(&ws, "chore: Release", Action::Tag);
+            let release_id = head(&ws);
+
+            rollback(&ws, &head_id, journal);
+            assert_eq!(head(&ws), release_id);
+            assert_eq!(tags(&ws), "");
+        }
+
+        #[test]
+        fn after_tagging() {
+            let ws = workspace("publish = false");
+            let (head_id, journal) = release(&ws, "chore: Release", Action::Push);
+            let release_id = head(&ws);
+
+            rollback(&ws, &head_id, journal);
+            assert_eq!(head(&ws), release_id);
+            assert_eq!(tags(&ws), "bar-v0.1.1\nfoo-v0.1.1\n");
+        }
     }
 }
@@ -12,10 +12,17 @@ pub struct ReplaceStep {
     #[command(flatten)]
     workspace: clap_cargo::Workspace,
 
+    #[command(flatten)]
+    group: super::GroupArgs,
+
     /// Process all packages whose current version is unpublished
     #[arg(long)]
     unpublished: bool,
 
+    /// Process packages changed since their last release
+    #[arg(long, conflicts_with = "unpublished")]
+    changed: bool,
+
     /// Custom config file
     #[arg(short, long = "config")]
     custom_config: Option<std::path::PathBuf>,
@@ -62,14 +69,16 @@ impl ReplaceStep {
             .exec()?;
         let config = self.to_config();
         let ws_config = crate::config::load_workspace_config(&config, &ws_meta)?;
+        let workspace = self.group.select(&self.workspace, &ws_config, &ws_meta)?;
         let mut pkgs = plan::load(&config, &ws_meta)?;
 
-        let (_selected_pkgs, excluded_pkgs) =
-            if self.unpublished && self.workspace == clap_cargo::Workspace::default() {
-                ws_meta.packages.iter().partition(|_| false)
-            } else {
-                self.workspace.partition_packages(&ws_meta)
-            };
+        let excluded_pkgs = super::deselected_packages(
+            &ws_meta,
+            &workspace,
+            &pkgs,
+            self.changed,
+            self.unpublished && workspace == clap_cargo::Workspace::default(),
+        );
         for excluded_pkg in excluded_pkgs {
             let Some(pkg) = pkgs.get_mut(&excluded_pkg.id) else {
                 // Either not in workspace or marked as `release = false`.
@@ -80,7 +89,7 @@ impl ReplaceStep {
             }
 
             let crate_name = pkg.meta.name.as_str();
-            let explicitly_excluded = self.workspace.exclude.contains(&excluded_pkg.name);
+            let explicitly_excluded = workspace.exclude.contains(&excluded_pkg.name);
             // 1. Don't show this message if already not releasing in config
             // 2. Still respect `--exclude`
             if pkg.config.release()
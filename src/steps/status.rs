@@ -0,0 +1,304 @@
+use std::io::Write as _;
+
+use crate::error::CliError;
+use crate::ops::git;
+use crate::steps::plan;
+
+/// Summarize the release state of each workspace member
+#[derive(Debug, Clone, clap::Args)]
+pub struct StatusStep {
+    #[command(flatten)]
+    manifest: clap_cargo::Manifest,
+
+    /// Custom config file
+    #[arg(short, long = "config", value_name = "PATH")]
+    custom_config: Option<std::path::PathBuf>,
+
+    /// Ignore implicit configuration files.
+    #[arg(long)]
+    isolated: bool,
+
+    /// Unstable options
+    #[arg(short = 'Z', value_name = "FEATURE")]
+    z: Vec<crate::config::UnstableValues>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t)]
+    output_format: super::OutputFormat,
+}
+
+impl StatusStep {
+    pub fn run(&self) -> Result<(), CliError> {
+        git::git_version()?;
+
+        let ws_meta = self
+            .manifest
+            .metadata()
+            // When evaluating dependency ordering, we need to consider optional dependencies
+            .features(cargo_metadata::CargoOpt::AllFeatures)
+            .exec()?;
+        let config = self.to_config();
+        let pkgs = plan::load(&config, &ws_meta)?;
+
+        let mut index = crate::ops::index::CratesIoIndex::new();
+        let workspace_root = ws_meta.workspace_root.as_std_path();
+        let mut statuses = Vec::new();
+        for pkg in pkgs.values() {
+            statuses.push(PackageStatus::new(workspace_root, pkg, &mut index)?);
+        }
+
+        let mut output = match self.output_format {
+            super::OutputFormat::Text => render_table(&statuses),
+            super::OutputFormat::Json => {
+                serde_json::to_string_pretty(&statuses).map_err(anyhow::Error::from)?
+            }
+        };
+        output.push('\n');
+        std::io::stdout().write_all(output.as_bytes())?;
+
+        Ok(())
+    }
+
+    fn to_config(&self) -> crate::config::ConfigArgs {
+        crate::config::ConfigArgs {
+            custom_config: self.custom_config.clone(),
+            isolated: self.isolated,
+            z: self.z.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct PackageStatus {
+    name: String,
+    version: String,
+    last_tag: Option<String>,
+    /// Only checked for packages that get published
+    registry_version: Option<String>,
+    published: Option<bool>,
+    /// Only known when there is a `last_tag`
+    unreleased_commits: Option<usize>,
+    suggested_bump: Option<String>,
+}
+
+impl PackageStatus {
+    fn new(
+        workspace_root: &std::path::Path,
+        pkg: &plan::PackageRelease,
+        index: &mut crate::ops::index::CratesIoIndex,
+    ) -> Result<Self, CliError> {
+        let crate_name = pkg.meta.name.as_str();
+        let version = &pkg.initial_version.full_version_string;
+
+        let (registry_version, published) = if pkg.config.publish() {
+            match index.krate(pkg.config.registry(), crate_name, pkg.config.certs_source()) {
+                Ok(Some(krate)) => {
+                    let published = krate.versions.iter().any(|v| v.version == *version);
+                    (
+                        Some(krate.highest_version().version.to_string()),
+                        Some(published),
+                    )
+                }
+                Ok(None) => (None, Some(false)),
+                Err(err) => {
+                    let _ = crate::ops::shell::warn(format!(
+                        "failed to read {} from {}: {err}",
+                        crate_name,
+                        pkg.config.registry().unwrap_or("crates.io")
+                    ));
+                    (None, None)
+                }
+            }
+        } else {
+            (None, None)
+        };
+
+        let (unreleased_commits, suggested_bump) = if let Some(prior_tag) = &pkg.prior_tag {
+            let commits = super::changes::package_commits(workspace_root, pkg, prior_tag)?;
+            let suggested_bump = pkg.suggested_level(&commits)?.map(|l| l.to_string());
+            (Some(commits.len()), suggested_bump)
+        } else {
+            (None, None)
+        };
+
+        Ok(Self {
+            name: crate_name.to_owned(),
+            version: version.clone(),
+            last_tag: pkg.prior_tag.clone(),
+            registry_version,
+            published,
+            unreleased_commits,
+            suggested_bump,
+        })
+    }
+}
+
+fn render_table(statuses: &[PackageStatus]) -> String {
+    const NONE: &str = "-";
+
+    let header = [
+        "PACKAGE",
+        "VERSION",
+        "LAST TAG",
+        "REGISTRY",
+        "PUBLISHED",
+        "UNRELEASED",
+        "BUMP",
+    ]
+    .map(ToOwned::to_owned);
+    let rows = statuses.iter().map(|s| {
+        [
+            s.name.clone(),
+            s.version.clone(),
+            s.last_tag.clone().unwrap_or_else(|| NONE.to_owned()),
+            s.registry_version
+                .clone()
+                .unwrap_or_else(|| NONE.to_owned()),
+            match s.published {
+                Some(true) => "yes".to_owned(),
+                Some(false) => "no".to_owned(),
+                None => NONE.to_owned(),
+            },
+            s.unreleased_commits
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| NONE.to_owned()),
+            s.suggested_bump.clone().unwrap_or_else(|| NONE.to_owned()),
+        ]
+    });
+    let table = std::iter::once(header).chain(rows).collect::<Vec<_>>();
+
+    let mut widths = [0; 7];
+    for row in &table {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    table
+        .iter()
+        .map(|row| {
+            row.iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_owned()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn statuses() -> Vec<PackageStatus> {
+        vec![
+            PackageStatus {
+                name: "foo".to_owned(),
+                version: "0.1.0".to_owned(),
+                last_tag: Some("foo-v0.1.0".to_owned()),
+                registry_version: Some("0.1.0".to_owned()),
+                published: Some(true),
+                unreleased_commits: Some(12),
+                suggested_bump: Some("minor".to_owned()),
+            },
+            PackageStatus {
+                name: "foo-internal".to_owned(),
+                version: "0.2.0-alpha.1".to_owned(),
+                last_tag: None,
+                registry_version: None,
+                published: None,
+                unreleased_commits: None,
+                suggested_bump: None,
+            },
+        ]
+    }
+
+    mod render_table {
+        use super::*;
+
+        #[test]
+        fn aligns_columns() {
+            let expected = "\
+PACKAGE       VERSION        LAST TAG    REGISTRY  PUBLISHED  UNRELEASED  BUMP
+foo           0.1.0          foo-v0.1.0  0.1.0     yes        12          minor
+foo-internal  0.2.0-alpha.1  -           -         -          -           -";
+            assert_eq!(render_table(&statuses()), expected);
+        }
+
+        #[test]
+        fn unpublished() {
+            let mut statuses = statuses();
+            statuses.truncate(1);
+            statuses[0].published = Some(false);
+            let table = render_table(&statuses);
+            assert_eq!(
+                table.lines().nth(1),
+                Some("foo      0.1.0    foo-v0.1.0  0.1.0     no         12          minor")
+            );
+        }
+    }
+
+    mod json {
+        use super::*;
+
+        #[test]
+        fn kebab_case_with_nulls() {
+            let json = serde_json::to_value(statuses()).unwrap();
+            assert_eq!(
+                json,
+                serde_json::json!([
+                    {
+                        "name": "foo",
+                        "version": "0.1.0",
+                        "last-tag": "foo-v0.1.0",
+                        "registry-version": "0.1.0",
+                        "published": true,
+                        "unreleased-commits": 12,
+                        "suggested-bump": "minor",
+                    },
+                    {
+                        "name": "foo-internal",
+                        "version": "0.2.0-alpha.1",
+                        "last-tag": null,
+                        "registry-version": null,
+                        "published": null,
+                        "unreleased-commits": null,
+                        "suggested-bump": null,
+                    },
+                ])
+            );
+        }
+    }
+
+    mod package_status {
+        use super::*;
+
+        use crate::steps::fixture::{Workspace, id};
+
+        #[test]
+        fn unreleased_commits() {
+            let ws = Workspace::new("publish = false", &[("foo", "0.1.0", &[])]);
+            ws.tag("foo-v0.1.0");
+            ws.write("foo/src/lib.rs", "pub fn new() {}\n");
+            ws.commit("feat: Add new");
+            ws.write("foo/src/lib.rs", "pub fn new() {}\n\npub fn old() {}\n");
+            ws.commit("fix: Restore old");
+
+            let ws_meta = ws.metadata();
+            let pkgs = ws.load(&ws_meta);
+            let mut index = crate::ops::index::CratesIoIndex::new();
+            let status =
+                PackageStatus::new(ws.root(), &pkgs[id(&pkgs, "foo")], &mut index).unwrap();
+            assert_eq!(status.last_tag.as_deref(), Some("foo-v0.1.0"));
+            assert_eq!(status.unreleased_commits, Some(2));
+            assert_eq!(status.suggested_bump.as_deref(), Some("patch"));
+            assert_eq!(status.registry_version, None);
+            assert_eq!(status.published, None);
+        }
+    }
+}
@@ -17,6 +17,9 @@ pub struct TagStep {
     #[command(flatten)]
     workspace: clap_cargo::Workspace,
 
+    #[command(flatten)]
+    group: super::GroupArgs,
+
     /// Custom config file
     #[arg(short, long = "config")]
     custom_config: Option<std::path::PathBuf>,
@@ -65,9 +68,10 @@ impl TagStep {
             .exec()?;
         let config = self.to_config();
         let ws_config = crate::config::load_workspace_config(&config, &ws_meta)?;
+        let workspace = self.group.select(&self.workspace, &ws_config, &ws_meta)?;
         let mut pkgs = plan::load(&config, &ws_meta)?;
 
-        let (_selected_pkgs, excluded_pkgs) = self.workspace.partition_packages(&ws_meta);
+        let (_selected_pkgs, excluded_pkgs) = workspace.partition_packages(&ws_meta);
         for excluded_pkg in excluded_pkgs {
             let Some(pkg) = pkgs.get_mut(&excluded_pkg.id) else {
                 // Either not in workspace or marked as `release = false`.
@@ -179,7 +183,23 @@ pub fn tag(pkgs: &[plan::PackageRelease], dry_run: bool) -> Result<(), CliError>
                 tag_name: Some(tag_name),
                 ..Default::default()
             };
-            let tag_message = template.render(pkg.config.tag_message());
+            let mut tag_message = template.render(pkg.config.tag_message());
+            if pkg.config.workspace_tag() {
+                tag_message.push('\n');
+                for member in pkgs
+                    .iter()
+                    .filter(|p| p.planned_tag.as_ref() == Some(tag_name))
+                {
+                    let version = member
+                        .planned_version
+                        .as_ref()
+                        .unwrap_or(&member.initial_version);
+                    tag_message.push_str(&format!(
+                        "\n- {} {}",
+                        member.meta.name, version.full_version_string
+                    ));
+                }
+            }
 
             log::debug!("creating git tag {tag_name}");
             if !git::tag(cwd, tag_name, &tag_message, pkg.config.sign_tag(), dry_run)? {
@@ -191,3 +211,39 @@ pub fn tag(pkgs: &[plan::PackageRelease], dry_run: bool) -> Result<(), CliError>
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod tag {
+        use super::*;
+
+        use crate::steps::BumpLevel;
+        use crate::steps::fixture::Workspace;
+
+        #[test]
+        fn workspace_tag_message() {
+            let ws = Workspace::new(
+                "workspace-tag = true\nshared-version = true\n",
+                &[("foo", "0.1.0", &[]), ("bar", "0.1.0", &[])],
+            );
+            let ws_meta = ws.metadata();
+            let planned = ws.plan(&ws_meta, BumpLevel::Minor);
+
+            tag(&planned, false).unwrap();
+            assert_eq!(ws.git(&["tag", "--list"]), "v0.2.0\n");
+            let message = ws.git(&["tag", "--list", "--format=%(contents)", "v0.2.0"]);
+            let mut members = message
+                .lines()
+                .filter(|l| l.starts_with("- "))
+                .collect::<Vec<_>>();
+            members.sort_unstable();
+            assert!(
+                message.starts_with("chore: Release version 0.2.0\n\n"),
+                "{message}"
+            );
+            assert_eq!(members, ["- bar 0.2.0", "- foo 0.2.0"]);
+        }
+    }
+}
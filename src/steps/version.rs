@@ -12,6 +12,14 @@ pub struct VersionStep {
     #[command(flatten)]
     workspace: clap_cargo::Workspace,
 
+    #[command(flatten)]
+    group: super::GroupArgs,
+
+    /// Process packages changed since their last release, along with dependents whose requirement
+    /// on them must move
+    #[arg(long)]
+    changed: bool,
+
     /// Custom config file
     #[arg(short, long = "config")]
     custom_config: Option<std::path::PathBuf>,
@@ -39,7 +47,8 @@ pub struct VersionStep {
     #[arg(long)]
     no_confirm: bool,
 
-    /// Either bump by LEVEL or set the VERSION for all selected packages
+    /// Either bump by LEVEL, including any of the `pre-release-channels` like `beta`, or set the
+    /// VERSION for all selected packages
     #[arg(value_name = "LEVEL|VERSION", help_heading = "Version")]
     level_or_version: super::TargetVersion,
 
@@ -69,7 +78,9 @@ impl VersionStep {
             .exec()?;
         let config = self.to_config();
         let ws_config = crate::config::load_workspace_config(&config, &ws_meta)?;
+        let workspace = self.group.select(&self.workspace, &ws_config, &ws_meta)?;
         let mut pkgs = plan::load(&config, &ws_meta)?;
+        super::validate_target(&self.level_or_version, &pkgs)?;
 
         for pkg in pkgs.values_mut() {
             if let Some(prev_tag) = self.prev_tag_name.as_ref() {
@@ -78,11 +89,13 @@ impl VersionStep {
                 pkg.set_prior_tag(prev_tag.to_owned());
             }
             if pkg.config.release() {
-                pkg.bump(&self.level_or_version, self.metadata.as_deref())?;
+                pkg.bump(&ws_meta, &self.level_or_version, self.metadata.as_deref())?;
             }
         }
 
-        let (_selected_pkgs, excluded_pkgs) = self.workspace.partition_packages(&ws_meta);
+        let excluded_pkgs =
+            super::deselected_packages(&ws_meta, &workspace, &pkgs, self.changed, false);
+        let mut deselected = std::collections::HashSet::new();
         for excluded_pkg in excluded_pkgs {
             let Some(pkg) = pkgs.get_mut(&excluded_pkg.id) else {
                 // Either not in workspace or marked as `release = false`.
@@ -94,9 +107,12 @@ impl VersionStep {
 
             pkg.planned_version = None;
             pkg.config.release = Some(false);
+            if !workspace.exclude.contains(&excluded_pkg.name) {
+                deselected.insert(pkg.meta.id.clone());
+            }
         }
 
-        let pkgs = plan::plan(pkgs)?;
+        let pkgs = plan::plan_with_dependents(pkgs, &deselected)?;
 
         let (selected_pkgs, excluded_pkgs): (Vec<_>, Vec<_>) = pkgs
             .into_iter()